@@ -0,0 +1,263 @@
+//! Account delta Merkle proofs.
+//!
+//! Each account modified in a slot is hashed with blake3 over its lamports,
+//! rent epoch, data, executable flag, owner and address. The account hashes,
+//! sorted by address, are the leaves of a 16-ary tree whose nodes are the
+//! sha256 of their children's hashes concatenated; its root is the slot's
+//! `account_delta_root`. A proof gives, for each level from the leaf upwards,
+//! the position of the node among its siblings and the sibling hashes.
+
+use crate::public_values::{
+    Account, AccountDeltaProof, ConsensusPackage, Hash, MerkleProof, Pubkey,
+};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+
+/// Number of children of an inner node
+pub const MERKLE_FANOUT: usize = 16;
+
+/// Why an account delta proof does not hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountProofError {
+    /// The proof is keyed by a different address than the account it carries
+    PubkeyMismatch { pubkey: Pubkey, account: Pubkey },
+    /// The account hash is not the hash of the account state
+    AccountHashMismatch {
+        pubkey: Pubkey,
+        committed: Hash,
+        computed: Hash,
+    },
+    /// A level of the Merkle path is inconsistent
+    MalformedPath { pubkey: Pubkey, level: usize },
+    /// The Merkle path does not lead to the slot's account delta root
+    RootMismatch {
+        pubkey: Pubkey,
+        slot: u64,
+        root: Hash,
+        computed: Hash,
+    },
+    /// There is no slot data holding the account delta root
+    MissingSlotData { pubkey: Pubkey, slot: u64 },
+}
+
+impl AccountProofError {
+    /// Address of the account whose proof failed
+    pub fn pubkey(&self) -> &Pubkey {
+        match self {
+            AccountProofError::PubkeyMismatch { pubkey, .. }
+            | AccountProofError::AccountHashMismatch { pubkey, .. }
+            | AccountProofError::MalformedPath { pubkey, .. }
+            | AccountProofError::RootMismatch { pubkey, .. }
+            | AccountProofError::MissingSlotData { pubkey, .. } => pubkey,
+        }
+    }
+}
+
+impl fmt::Display for AccountProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b58 = |bytes: &[u8; 32]| bs58::encode(bytes).into_string();
+        match self {
+            AccountProofError::PubkeyMismatch { pubkey, account } => write!(
+                f,
+                "proof for account {} carries account {}",
+                b58(pubkey),
+                b58(account)
+            ),
+            AccountProofError::AccountHashMismatch {
+                pubkey,
+                committed,
+                computed,
+            } => write!(
+                f,
+                "account {} has hash {}, but its state hashes to {}",
+                b58(pubkey),
+                b58(committed),
+                b58(computed)
+            ),
+            AccountProofError::MalformedPath { pubkey, level } => write!(
+                f,
+                "Merkle path of account {} is malformed at level {}",
+                b58(pubkey),
+                level
+            ),
+            AccountProofError::RootMismatch {
+                pubkey,
+                slot,
+                root,
+                computed,
+            } => write!(
+                f,
+                "Merkle path of account {} leads to {}, but the account delta root of slot {} is {}",
+                b58(pubkey),
+                b58(computed),
+                slot,
+                b58(root)
+            ),
+            AccountProofError::MissingSlotData { pubkey, slot } => write!(
+                f,
+                "no slot data for slot {} to check account {} against",
+                slot,
+                b58(pubkey)
+            ),
+        }
+    }
+}
+
+impl Error for AccountProofError {}
+
+/// Solana's hash of an account state. Accounts without lamports hash to zero.
+pub fn hash_account(account: &Account) -> Hash {
+    if account.lamports == 0 {
+        return Hash::default();
+    }
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&account.lamports.to_le_bytes());
+    hasher.update(&account.rent_epoch.to_le_bytes());
+    hasher.update(&account.data);
+    hasher.update(&[account.executable as u8]);
+    hasher.update(&account.owner);
+    hasher.update(&account.pubkey);
+    *hasher.finalize().as_bytes()
+}
+
+/// Walk a Merkle path from a leaf to the root it implies.
+///
+/// Returns the level at which the path is malformed: a position outside the
+/// node's children or more children than the fanout.
+pub fn compute_merkle_root(leaf: Hash, proof: &MerkleProof) -> Result<Hash, usize> {
+    if proof.path.len() != proof.siblings.len() {
+        return Err(proof.path.len().min(proof.siblings.len()));
+    }
+
+    let mut node = leaf;
+    for (level, (&position, siblings)) in proof.path.iter().zip(&proof.siblings).enumerate() {
+        if position > siblings.len() || siblings.len() >= MERKLE_FANOUT {
+            return Err(level);
+        }
+        let mut hasher = Sha256::new();
+        for sibling in &siblings[..position] {
+            hasher.update(sibling);
+        }
+        hasher.update(node);
+        for sibling in &siblings[position..] {
+            hasher.update(sibling);
+        }
+        node = hasher.finalize().into();
+    }
+    Ok(node)
+}
+
+/// Verify one account delta proof against the account delta root of its slot
+pub fn verify_account_proof(
+    slot: u64,
+    root: &Hash,
+    (pubkey, (entry, proof)): &AccountDeltaProof,
+) -> Result<(), AccountProofError> {
+    let pubkey = *pubkey;
+    if entry.pubkey != pubkey || entry.account.pubkey != pubkey {
+        let account = if entry.pubkey != pubkey {
+            entry.pubkey
+        } else {
+            entry.account.pubkey
+        };
+        return Err(AccountProofError::PubkeyMismatch { pubkey, account });
+    }
+
+    let computed = hash_account(&entry.account);
+    if computed != entry.hash {
+        return Err(AccountProofError::AccountHashMismatch {
+            pubkey,
+            committed: entry.hash,
+            computed,
+        });
+    }
+
+    let computed = compute_merkle_root(entry.hash, proof)
+        .map_err(|level| AccountProofError::MalformedPath { pubkey, level })?;
+    if computed != *root {
+        return Err(AccountProofError::RootMismatch {
+            pubkey,
+            slot,
+            root: *root,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Verify every account delta proof of a package, returning the slot of each failure
+pub fn verify_account_proofs(package: &ConsensusPackage) -> Vec<(u64, AccountProofError)> {
+    let mut failures = Vec::new();
+    for (&slot, proofs) in &package.proofs {
+        let root = package
+            .slot_data
+            .get(&slot)
+            .map(|data| data.account_delta_root);
+        for proof in proofs {
+            let result = match &root {
+                Some(root) => verify_account_proof(slot, root, proof),
+                None => Err(AccountProofError::MissingSlotData {
+                    pubkey: proof.0,
+                    slot,
+                }),
+            };
+            if let Err(error) = result {
+                failures.push((slot, error));
+            }
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::load_package;
+    use std::path::Path;
+
+    fn sample_package() -> ConsensusPackage {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/package_solana.json");
+        load_package(Path::new(path)).unwrap()
+    }
+
+    /// The slot and account delta root of the single sample proof, and the proof
+    fn sample_proof() -> (u64, Hash, AccountDeltaProof) {
+        let package = sample_package();
+        let (&slot, proofs) = package.proofs.iter().next().unwrap();
+        let root = package.slot_data[&slot].account_delta_root;
+        (slot, root, proofs[0].clone())
+    }
+
+    #[test]
+    fn verifies_sample_account_proof() {
+        let package = sample_package();
+        assert_eq!(package.proofs.values().map(Vec::len).sum::<usize>(), 1);
+        assert_eq!(verify_account_proofs(&package), vec![]);
+    }
+
+    #[test]
+    fn rejects_modified_account_data() {
+        let (slot, root, mut proof) = sample_proof();
+        let data = &mut (proof.1).0.account.data;
+        assert!(!data.is_empty());
+        data[0] ^= 0x01;
+
+        assert!(matches!(
+            verify_account_proof(slot, &root, &proof),
+            Err(AccountProofError::AccountHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_position_outside_siblings() {
+        let (slot, root, mut proof) = sample_proof();
+        let merkle = &mut (proof.1).1;
+        merkle.path[0] = merkle.siblings[0].len() + 1;
+
+        assert!(matches!(
+            verify_account_proof(slot, &root, &proof),
+            Err(AccountProofError::MalformedPath { level: 0, .. })
+        ));
+    }
+}
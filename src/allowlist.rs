@@ -0,0 +1,144 @@
+use crate::error::VerifyError;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// A known-good consensus program verification key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowedVkey {
+    /// Program vkey hash in `bytes32()` form
+    pub hash: String,
+    /// Human readable label, such as the program release version
+    pub label: String,
+}
+
+/// Pinned list of consensus program vkey hashes that proofs may be verified against.
+///
+/// The list is read from a JSON file of the form
+/// `{"vkeys": [{"hash": "0x00cb...", "label": "v1.0.0"}]}` so that program
+/// versions can be added and retired without rebuilding the verifier.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VkeyAllowList {
+    /// The allowed verification keys
+    pub vkeys: Vec<AllowedVkey>,
+}
+
+/// Normalize a vkey hash to lowercase with a `0x` prefix
+pub fn normalize_vkey_hash(hash: &str) -> String {
+    let hash = hash.trim().to_lowercase();
+    match hash.strip_prefix("0x") {
+        Some(_) => hash,
+        None => format!("0x{}", hash),
+    }
+}
+
+impl VkeyAllowList {
+    /// Load an allow-list JSON file
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let bytes = fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Returns true if no vkeys are listed
+    pub fn is_empty(&self) -> bool {
+        self.vkeys.is_empty()
+    }
+
+    /// Find the entry for a vkey hash
+    pub fn lookup(&self, hash: &str) -> Option<&AllowedVkey> {
+        let hash = normalize_vkey_hash(hash);
+        self.vkeys
+            .iter()
+            .find(|vkey| normalize_vkey_hash(&vkey.hash) == hash)
+    }
+
+    /// Check that a vkey hash is allowed, returning its label
+    pub fn check(&self, hash: &str) -> Result<String, VerifyError> {
+        match self.lookup(hash) {
+            Some(vkey) => Ok(vkey.label.clone()),
+            None => Err(VerifyError::VkeyNotAllowed {
+                vkey_hash: normalize_vkey_hash(hash),
+            }),
+        }
+    }
+}
+
+/// Parse a `<hash>[=<label>]` allow-list entry
+pub fn parse_allowed_vkey(s: &str) -> Result<AllowedVkey, String> {
+    let (hash, label) = match s.split_once('=') {
+        Some((hash, label)) => (hash, label.trim()),
+        None => (s, ""),
+    };
+
+    let hash = normalize_vkey_hash(hash);
+    if hash.len() != 66 || hex::decode(&hash[2..]).is_err() {
+        return Err(format!(
+            "invalid vkey hash '{}', expected 32 bytes of hex",
+            s
+        ));
+    }
+
+    let label = if label.is_empty() {
+        hash.clone()
+    } else {
+        label.to_string()
+    };
+    Ok(AllowedVkey { hash, label })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Program vkey hash of the sample proof
+    const SAMPLE: &str = "0x00cb04968f45082eb851c86ccbf309b0407afcc28121a81f87438a95ddc9c18c";
+
+    #[test]
+    fn normalizes_prefix_and_case() {
+        let unprefixed = &SAMPLE[2..];
+        let mixed_case = format!("0X{}", unprefixed.to_uppercase());
+
+        for input in [SAMPLE, unprefixed, &mixed_case, &format!(" {} ", SAMPLE)] {
+            assert_eq!(normalize_vkey_hash(input), SAMPLE);
+            assert_eq!(parse_allowed_vkey(input).unwrap().hash, SAMPLE);
+        }
+    }
+
+    #[test]
+    fn parses_optional_label() {
+        assert_eq!(
+            parse_allowed_vkey(&format!("{}= v1.0.0", SAMPLE)),
+            Ok(AllowedVkey {
+                hash: SAMPLE.to_string(),
+                label: "v1.0.0".to_string(),
+            })
+        );
+        assert_eq!(parse_allowed_vkey(SAMPLE).unwrap().label, SAMPLE);
+    }
+
+    #[test]
+    fn rejects_malformed_hashes() {
+        assert!(parse_allowed_vkey(&SAMPLE[..64]).is_err());
+        assert!(parse_allowed_vkey(&format!("{}00", SAMPLE)).is_err());
+        assert!(parse_allowed_vkey(&format!("0x{}", "zz".repeat(32))).is_err());
+        assert!(parse_allowed_vkey("").is_err());
+    }
+
+    #[test]
+    fn checks_allowed_and_unknown_hashes() {
+        let allowed = VkeyAllowList {
+            vkeys: vec![parse_allowed_vkey(&format!("{}=v1.0.0", &SAMPLE[2..])).unwrap()],
+        };
+
+        assert_eq!(
+            allowed.check(&SAMPLE.to_uppercase().replace("0X", "0x")),
+            Ok("v1.0.0".to_string())
+        );
+        let other = format!("0x01{}", &SAMPLE[4..]);
+        assert_eq!(
+            allowed.check(&other),
+            Err(VerifyError::VkeyNotAllowed { vkey_hash: other })
+        );
+    }
+}
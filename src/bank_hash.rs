@@ -0,0 +1,160 @@
+//! Solana bank hash recomputation.
+//!
+//! A bank hash commits to the parent bank hash, the accounts delta hash, the
+//! number of signatures processed and the last blockhash of the slot:
+//!
+//! `sha256(parent_bankhash || account_delta_root || num_sigs as u64 LE || blockhash)`
+//!
+//! On some slots the validator mixes in further hashes: the epoch accounts
+//! hash on the slot it is included in, the accounts lattice hash checksum once
+//! that feature is active, and the hard fork count on a hard-forked slot.
+//! None of these are part of the slot data; pass them as [`BankHashExtras`].
+//! Proof verification only recomputes bank hashes when asked to, with the
+//! extras of each slot that needs them.
+
+use crate::error::VerifyError;
+use crate::public_values::{ConsensusPackage, Hash, SlotData};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Feature-gated inputs mixed into the bank hash of some slots
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BankHashExtras {
+    /// Epoch accounts hash, on the slot that includes it
+    pub epoch_accounts_hash: Option<Hash>,
+    /// Checksum of the accounts lattice hash, once that feature is active
+    pub accounts_lt_hash_checksum: Option<Hash>,
+    /// Number of hard forks at this slot, on a hard-forked slot
+    pub hard_fork_count: Option<u64>,
+}
+
+fn hashv(parts: &[&[u8]]) -> Hash {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Compute the bank hash of a slot from its components
+pub fn compute_bank_hash(data: &SlotData, extras: &BankHashExtras) -> Hash {
+    let mut hash = hashv(&[
+        &data.parent_bankhash,
+        &data.account_delta_root,
+        &data.num_sigs.to_le_bytes(),
+        &data.blockhash,
+    ]);
+    if let Some(epoch_accounts_hash) = &extras.epoch_accounts_hash {
+        hash = hashv(&[&hash, epoch_accounts_hash]);
+    }
+    if let Some(checksum) = &extras.accounts_lt_hash_checksum {
+        hash = hashv(&[&hash, checksum]);
+    }
+    if let Some(count) = extras.hard_fork_count {
+        hash = hashv(&[&hash, &count.to_le_bytes()]);
+    }
+    hash
+}
+
+/// Check that the bank hash of a slot matches its components.
+///
+/// Slots without a bank hash have nothing to check.
+pub fn check_bank_hash(
+    slot: u64,
+    data: &SlotData,
+    extras: &BankHashExtras,
+) -> Result<(), VerifyError> {
+    let Some(committed) = data.bank_hash else {
+        return Ok(());
+    };
+    let computed = compute_bank_hash(data, extras);
+    if committed != computed {
+        return Err(VerifyError::BankHashMismatch {
+            slot,
+            committed: bs58::encode(committed).into_string(),
+            computed: bs58::encode(computed).into_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Check the bank hash of every slot of a package.
+///
+/// Slots without an entry in `extras` are assumed to have none.
+pub fn check_package_bank_hashes(
+    package: &ConsensusPackage,
+    extras: &BTreeMap<u64, BankHashExtras>,
+) -> Result<(), VerifyError> {
+    let none = BankHashExtras::default();
+    package.slot_data.iter().try_for_each(|(slot, data)| {
+        check_bank_hash(*slot, data, extras.get(slot).unwrap_or(&none))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::load_package;
+    use std::path::Path;
+
+    fn sample_package() -> ConsensusPackage {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/package_solana.json");
+        load_package(Path::new(path)).unwrap()
+    }
+
+    #[test]
+    fn recomputes_sample_bank_hash() {
+        let package = sample_package();
+        let data = &package.slot_data[&package.first_slot];
+
+        assert_eq!(
+            Some(compute_bank_hash(data, &BankHashExtras::default())),
+            data.bank_hash
+        );
+        assert_eq!(
+            check_package_bank_hashes(&package, &BTreeMap::new()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn mixes_in_extras_in_order() {
+        let package = sample_package();
+        let data = &package.slot_data[&package.first_slot];
+        let extras = BankHashExtras {
+            epoch_accounts_hash: Some([1; 32]),
+            accounts_lt_hash_checksum: Some([2; 32]),
+            hard_fork_count: Some(3),
+        };
+
+        let base = compute_bank_hash(data, &BankHashExtras::default());
+        let with_eah: Hash = Sha256::new()
+            .chain_update(base)
+            .chain_update([1; 32])
+            .finalize()
+            .into();
+        let with_lt_hash: Hash = Sha256::new()
+            .chain_update(with_eah)
+            .chain_update([2; 32])
+            .finalize()
+            .into();
+        let expected: Hash = Sha256::new()
+            .chain_update(with_lt_hash)
+            .chain_update(3u64.to_le_bytes())
+            .finalize()
+            .into();
+        assert_eq!(compute_bank_hash(data, &extras), expected);
+    }
+
+    #[test]
+    fn rejects_tampered_num_sigs() {
+        let mut package = sample_package();
+        let slot = package.first_slot;
+        package.slot_data.get_mut(&slot).unwrap().num_sigs += 1;
+
+        assert!(matches!(
+            check_package_bank_hashes(&package, &BTreeMap::new()),
+            Err(VerifyError::BankHashMismatch { slot: s, .. }) if s == slot
+        ));
+    }
+}
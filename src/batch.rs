@@ -0,0 +1,218 @@
+use crate::chain::{ChainBreak, ChainChecker};
+use crate::error::VerifyError;
+use crate::input::{parse_proof, parse_vkey_json, read_input};
+use crate::outcome::VerificationOutcome;
+use crate::proof_kind::ProofKind;
+use crate::public_values::ConsensusPackage;
+use crate::report::{InputFile, ReportError, Timings, VerificationReport};
+use crate::verifier::Verifier;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::SP1VerifyingKey;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// A proof file and the verification key file to verify it against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchEntry {
+    pub proof: PathBuf,
+    pub vkey: PathBuf,
+}
+
+/// Proofs to verify together.
+///
+/// Read from a JSON file of the form
+/// `{"proofs": [{"proof": "slot_1.json", "vkey": "vkey.json"}]}`. Relative
+/// paths are resolved against the directory of the manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchManifest {
+    pub proofs: Vec<BatchEntry>,
+}
+
+impl BatchManifest {
+    /// Load a manifest JSON file
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let bytes = fs::read(path)?;
+        let mut manifest: Self = serde_json::from_slice(&bytes)?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for entry in &mut manifest.proofs {
+            entry.proof = base.join(&entry.proof);
+            entry.vkey = base.join(&entry.vkey);
+        }
+        Ok(manifest)
+    }
+
+    /// List every proof matching a glob pattern, all to be verified against
+    /// one verification key. A directory is treated as all `*.json` files in it.
+    pub fn from_glob(pattern: &str, vkey: &Path) -> Result<Self, Box<dyn Error>> {
+        let pattern = if Path::new(pattern).is_dir() {
+            Path::new(pattern).join("*.json").display().to_string()
+        } else {
+            pattern.to_string()
+        };
+
+        let mut proofs = Vec::new();
+        for path in glob::glob(&pattern)? {
+            let path = path?;
+            // The verification key may live next to the proofs
+            if path != vkey {
+                proofs.push(BatchEntry {
+                    proof: path,
+                    vkey: vkey.to_path_buf(),
+                });
+            }
+        }
+        Ok(Self { proofs })
+    }
+}
+
+/// Result of verifying one proof of a batch
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResult {
+    /// The proof and verification key files
+    pub entry: BatchEntry,
+    /// What was checked, in the same form as a single verification
+    pub report: VerificationReport,
+    /// Why the proof was not accepted
+    #[serde(skip)]
+    pub error: Option<VerifyError>,
+    /// The verification outcome, if the proof could be loaded
+    #[serde(skip)]
+    pub outcome: Option<VerificationOutcome>,
+}
+
+impl BatchResult {
+    /// Returns true if the proof was accepted
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Result of verifying an ordered sequence of proofs as a chain
+#[derive(Debug, Clone)]
+pub struct ChainResult {
+    /// Result of each proof, in chain order
+    pub proofs: Vec<BatchResult>,
+    /// The consensus package of each valid proof, in chain order
+    pub packages: Vec<ConsensusPackage>,
+    /// Why the chain was not checked: the first proof that was not accepted
+    pub failure: Option<VerifyError>,
+    /// Each broken link, with the index of the later proof
+    pub breaks: Vec<(usize, ChainBreak)>,
+}
+
+impl ChainResult {
+    /// Returns true if every proof was accepted and every link holds
+    pub fn is_valid(&self) -> bool {
+        self.failure.is_none() && self.breaks.is_empty()
+    }
+}
+
+impl Verifier {
+    /// Verify an ordered sequence of proofs and check that consecutive proofs link up.
+    ///
+    /// The links are only checked once every proof is valid.
+    pub fn verify_chain(&self, manifest: &BatchManifest, checker: &ChainChecker) -> ChainResult {
+        let proofs = self.verify_batch(manifest);
+
+        let mut packages = Vec::with_capacity(proofs.len());
+        let mut failure = proofs.iter().find_map(|result| result.error.clone());
+        for outcome in proofs.iter().filter_map(|result| result.outcome.as_ref()) {
+            match outcome.consensus() {
+                Ok(output) => packages.push(output.package),
+                Err(e) => {
+                    failure.get_or_insert(VerifyError::MalformedPublicValues(e.to_string()));
+                }
+            }
+        }
+
+        let breaks = match failure {
+            Some(_) => Vec::new(),
+            None => checker.check(&packages),
+        };
+        ChainResult {
+            proofs,
+            packages,
+            failure,
+            breaks,
+        }
+    }
+
+    /// Verify a batch of proofs in parallel.
+    ///
+    /// Each distinct verification key is read once and shared by the proofs
+    /// that use it. Results are returned in manifest order.
+    pub fn verify_batch(&self, manifest: &BatchManifest) -> Vec<BatchResult> {
+        let mut vkeys = HashMap::new();
+        for entry in &manifest.proofs {
+            vkeys
+                .entry(entry.vkey.clone())
+                .or_insert_with(|| load_vkey_file(&entry.vkey));
+        }
+
+        manifest
+            .proofs
+            .par_iter()
+            .map(|entry| self.verify_entry(entry, &vkeys[&entry.vkey]))
+            .collect()
+    }
+
+    fn verify_entry(
+        &self,
+        entry: &BatchEntry,
+        vkey: &Result<(InputFile, SP1VerifyingKey), VerifyError>,
+    ) -> BatchResult {
+        let start = Instant::now();
+        let mut report = VerificationReport::default();
+
+        let (error, outcome) = match self.load_and_verify(entry, vkey, &mut report) {
+            Ok(outcome) => (outcome.error().cloned(), Some(outcome)),
+            Err(error) => {
+                report.error = Some(ReportError::from(&error));
+                (Some(error), None)
+            }
+        };
+        report.timings.total_ms = Timings::millis(start.elapsed());
+
+        BatchResult {
+            entry: entry.clone(),
+            report,
+            error,
+            outcome,
+        }
+    }
+
+    /// Load and verify one proof
+    fn load_and_verify(
+        &self,
+        entry: &BatchEntry,
+        vkey: &Result<(InputFile, SP1VerifyingKey), VerifyError>,
+        report: &mut VerificationReport,
+    ) -> Result<VerificationOutcome, VerifyError> {
+        let load_start = Instant::now();
+        let (file, bytes) = read_input(&entry.proof)?;
+        report.proof = Some(file);
+        let proof = parse_proof(&bytes)?;
+        report.proof_kind = Some(ProofKind::of(&proof.proof).to_string());
+
+        let (file, vk) = vkey.as_ref().map_err(Clone::clone)?;
+        report.vkey = Some(file.clone());
+        report.timings.load_ms = Timings::millis(load_start.elapsed());
+
+        let verify_start = Instant::now();
+        let outcome = self.verify(&proof, vk);
+        report.timings.verify_ms = Timings::millis(verify_start.elapsed());
+        report.record_outcome(&outcome);
+
+        Ok(outcome)
+    }
+}
+
+fn load_vkey_file(path: &Path) -> Result<(InputFile, SP1VerifyingKey), VerifyError> {
+    let (file, bytes) = read_input(path)?;
+    Ok((file, parse_vkey_json(&bytes)?))
+}
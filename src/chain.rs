@@ -0,0 +1,300 @@
+//! Continuity of a sequence of consensus proofs.
+//!
+//! Each proof covers `first_slot..=last_slot`. Two consecutive proofs link up
+//! when the second starts right after the first ends and the
+//! `parent_bankhash` of its first slot is the bank hash of the first proof's
+//! last slot. Ranges that are known not to be covered can be allowed as gaps,
+//! in which case the bank hash link across them is not checked.
+
+use crate::public_values::{ConsensusPackage, Hash};
+use std::error::Error;
+use std::fmt;
+
+/// Why two consecutive proofs do not form a continuous chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainBreak {
+    /// Slots between the proofs are not covered and no gap was allowed for them
+    Gap { first_slot: u64, last_slot: u64 },
+    /// The next proof starts at or before the last slot of the previous one
+    Overlap {
+        last_slot: u64,
+        next_first_slot: u64,
+    },
+    /// The previous proof commits no bank hash for its last slot
+    MissingBankHash { slot: u64 },
+    /// The next proof has no slot data for its first slot
+    MissingSlotData { slot: u64 },
+    /// The parent bank hash of the next proof's first slot is not the previous bank hash
+    ParentBankHash {
+        slot: u64,
+        expected: Hash,
+        actual: Hash,
+    },
+}
+
+impl fmt::Display for ChainBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainBreak::Gap {
+                first_slot,
+                last_slot,
+            } => write!(f, "slots {}..={} are not covered", first_slot, last_slot),
+            ChainBreak::Overlap {
+                last_slot,
+                next_first_slot,
+            } => write!(
+                f,
+                "proof starting at slot {} overlaps the previous proof ending at slot {}",
+                next_first_slot, last_slot
+            ),
+            ChainBreak::MissingBankHash { slot } => {
+                write!(f, "no bank hash committed for slot {}", slot)
+            }
+            ChainBreak::MissingSlotData { slot } => {
+                write!(f, "no slot data committed for slot {}", slot)
+            }
+            ChainBreak::ParentBankHash {
+                slot,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "parent bank hash of slot {} is {}, expected {}",
+                slot,
+                bs58::encode(actual).into_string(),
+                bs58::encode(expected).into_string()
+            ),
+        }
+    }
+}
+
+impl Error for ChainBreak {}
+
+/// Checks that consecutive consensus packages form a continuous chain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainChecker {
+    /// Inclusive slot ranges that may be left uncovered
+    pub allowed_gaps: Vec<(u64, u64)>,
+}
+
+impl ChainChecker {
+    /// Allow the slots `first_slot..=last_slot` to be left uncovered
+    pub fn with_allowed_gap(mut self, first_slot: u64, last_slot: u64) -> Self {
+        self.allowed_gaps.push((first_slot, last_slot));
+        self
+    }
+
+    fn is_allowed_gap(&self, first_slot: u64, last_slot: u64) -> bool {
+        self.allowed_gaps
+            .iter()
+            .any(|&(first, last)| first <= first_slot && last_slot <= last)
+    }
+
+    /// Check that `next` continues the chain where `previous` ends
+    pub fn check_link(
+        &self,
+        previous: &ConsensusPackage,
+        next: &ConsensusPackage,
+    ) -> Result<(), ChainBreak> {
+        if next.first_slot <= previous.last_slot {
+            return Err(ChainBreak::Overlap {
+                last_slot: previous.last_slot,
+                next_first_slot: next.first_slot,
+            });
+        }
+
+        if next.first_slot > previous.last_slot + 1 {
+            let (first_slot, last_slot) = (previous.last_slot + 1, next.first_slot - 1);
+            if self.is_allowed_gap(first_slot, last_slot) {
+                return Ok(());
+            }
+            return Err(ChainBreak::Gap {
+                first_slot,
+                last_slot,
+            });
+        }
+
+        let expected = previous
+            .slot_data
+            .get(&previous.last_slot)
+            .and_then(|data| data.bank_hash)
+            .ok_or(ChainBreak::MissingBankHash {
+                slot: previous.last_slot,
+            })?;
+        let actual = next
+            .slot_data
+            .get(&next.first_slot)
+            .map(|data| data.parent_bankhash)
+            .ok_or(ChainBreak::MissingSlotData {
+                slot: next.first_slot,
+            })?;
+
+        if actual != expected {
+            return Err(ChainBreak::ParentBankHash {
+                slot: next.first_slot,
+                expected,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check every link of a sequence, returning the index of the later
+    /// package of each broken link
+    pub fn check(&self, packages: &[ConsensusPackage]) -> Vec<(usize, ChainBreak)> {
+        packages
+            .windows(2)
+            .enumerate()
+            .filter_map(|(i, pair)| {
+                self.check_link(&pair[0], &pair[1])
+                    .err()
+                    .map(|chain_break| (i + 1, chain_break))
+            })
+            .collect()
+    }
+}
+
+/// Parse an inclusive slot range given as `<first>-<last>` or a single slot
+pub fn parse_slot_range(s: &str) -> Result<(u64, u64), String> {
+    let (first, last) = s.split_once('-').unwrap_or((s, s));
+    let parse = |slot: &str| {
+        slot.trim()
+            .parse::<u64>()
+            .map_err(|e| format!("invalid slot '{}': {}", slot, e))
+    };
+    let (first, last) = (parse(first)?, parse(last)?);
+    if first > last {
+        return Err(format!("slot range {} is empty", s));
+    }
+    Ok((first, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::load_package;
+    use std::path::Path;
+
+    /// The sample package moved to `first_slot..=last_slot`, with slot data for both ends
+    fn package(first_slot: u64, last_slot: u64, parent: Hash, bank_hash: Hash) -> ConsensusPackage {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/package_solana.json");
+        let mut package = load_package(Path::new(path)).unwrap();
+        let mut data = package.slot_data.values().next().unwrap().clone();
+        package.first_slot = first_slot;
+        package.last_slot = last_slot;
+        package.slot_data.clear();
+
+        data.parent_bankhash = parent;
+        data.bank_hash = Some(bank_hash);
+        package.slot_data.insert(first_slot, data.clone());
+        data.parent_bankhash = [0; 32];
+        package.slot_data.insert(last_slot, data);
+        package
+    }
+
+    #[test]
+    fn accepts_contiguous_link() {
+        let previous = package(10, 19, [1; 32], [2; 32]);
+        let next = package(20, 29, [2; 32], [3; 32]);
+
+        assert_eq!(ChainChecker::default().check_link(&previous, &next), Ok(()));
+    }
+
+    #[test]
+    fn rejects_overlapping_ranges() {
+        let previous = package(10, 19, [1; 32], [2; 32]);
+        let next = package(19, 29, [2; 32], [3; 32]);
+
+        assert_eq!(
+            ChainChecker::default().check_link(&previous, &next),
+            Err(ChainBreak::Overlap {
+                last_slot: 19,
+                next_first_slot: 19,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_gap_unless_allowed() {
+        let previous = package(10, 19, [1; 32], [2; 32]);
+        // The parent bank hash is not checked across an allowed gap
+        let next = package(25, 29, [9; 32], [3; 32]);
+
+        assert_eq!(
+            ChainChecker::default().check_link(&previous, &next),
+            Err(ChainBreak::Gap {
+                first_slot: 20,
+                last_slot: 24,
+            })
+        );
+        assert_eq!(
+            ChainChecker::default()
+                .with_allowed_gap(21, 24)
+                .check_link(&previous, &next),
+            Err(ChainBreak::Gap {
+                first_slot: 20,
+                last_slot: 24,
+            })
+        );
+        assert_eq!(
+            ChainChecker::default()
+                .with_allowed_gap(18, 24)
+                .check_link(&previous, &next),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rejects_parent_bank_hash_mismatch() {
+        let previous = package(10, 19, [1; 32], [2; 32]);
+        let next = package(20, 29, [4; 32], [3; 32]);
+
+        assert_eq!(
+            ChainChecker::default().check_link(&previous, &next),
+            Err(ChainBreak::ParentBankHash {
+                slot: 20,
+                expected: [2; 32],
+                actual: [4; 32],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_missing_bank_hash() {
+        let mut previous = package(10, 19, [1; 32], [2; 32]);
+        previous.slot_data.get_mut(&19).unwrap().bank_hash = None;
+        let next = package(20, 29, [2; 32], [3; 32]);
+
+        assert_eq!(
+            ChainChecker::default().check_link(&previous, &next),
+            Err(ChainBreak::MissingBankHash { slot: 19 })
+        );
+
+        previous.slot_data.remove(&19);
+        assert_eq!(
+            ChainChecker::default().check_link(&previous, &next),
+            Err(ChainBreak::MissingBankHash { slot: 19 })
+        );
+    }
+
+    #[test]
+    fn reports_index_of_each_broken_link() {
+        let packages = [
+            package(10, 19, [1; 32], [2; 32]),
+            package(20, 29, [2; 32], [3; 32]),
+            package(30, 39, [4; 32], [5; 32]),
+        ];
+
+        assert_eq!(
+            ChainChecker::default().check(&packages),
+            vec![(
+                2,
+                ChainBreak::ParentBankHash {
+                    slot: 30,
+                    expected: [3; 32],
+                    actual: [4; 32],
+                }
+            )]
+        );
+    }
+}
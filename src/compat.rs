@@ -0,0 +1,100 @@
+//! SP1 version compatibility.
+//!
+//! Groth16 and Plonk proofs are only valid for the circuit they were made
+//! with, and every proof records the circuit version of the SDK that made it
+//! in `sp1_version`. The linked SDK only verifies proofs of its own circuit
+//! version, so a proof from another version would otherwise surface as an
+//! opaque pairing failure.
+
+use crate::error::VerifyError;
+
+/// Version of `sp1-sdk` and `sp1-verifier` this crate is built against.
+/// Keep in sync with `Cargo.toml`.
+pub const SDK_VERSION: &str = "4.1.3";
+
+/// SDK release lines and the circuit version recorded in the proofs they make
+pub const CIRCUIT_VERSIONS: &[(&str, &str)] = &[("4.0", "v4.0.0-rc.3"), ("4.1", "v4.0.0-rc.3")];
+
+/// Circuit version recorded in proofs made by an SDK release
+pub fn circuit_version_for(sdk_version: &str) -> Option<&'static str> {
+    CIRCUIT_VERSIONS
+        .iter()
+        .find(|(release, _)| {
+            sdk_version
+                .strip_prefix(release)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+        .map(|(_, circuit_version)| *circuit_version)
+}
+
+/// SDK release lines that make proofs with a circuit version
+pub fn sdk_releases_for(circuit_version: &str) -> Vec<&'static str> {
+    CIRCUIT_VERSIONS
+        .iter()
+        .filter(|(_, version)| *version == circuit_version)
+        .map(|(release, _)| *release)
+        .collect()
+}
+
+/// Circuit version of the proofs the linked verifier accepts
+pub fn expected_circuit_version() -> &'static str {
+    #[cfg(feature = "prover")]
+    {
+        sp1_sdk::SP1_CIRCUIT_VERSION
+    }
+    #[cfg(not(feature = "prover"))]
+    {
+        circuit_version_for(SDK_VERSION).unwrap_or("unknown")
+    }
+}
+
+/// Describe a circuit version with the SDK releases that use it, if known
+fn describe(circuit_version: &str) -> String {
+    match sdk_releases_for(circuit_version).as_slice() {
+        [] => circuit_version.to_string(),
+        releases => format!("{} (sp1-sdk {})", circuit_version, releases.join(", ")),
+    }
+}
+
+/// Check that a proof's `sp1_version` is the circuit version the linked verifier accepts
+pub fn check_sp1_version(proof_version: &str) -> Result<(), VerifyError> {
+    let expected = expected_circuit_version();
+    if proof_version == expected {
+        return Ok(());
+    }
+    Err(VerifyError::VersionMismatch {
+        proof: describe(proof_version),
+        expected: describe(expected),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_sdk_releases_to_circuit_versions() {
+        assert_eq!(circuit_version_for("4.1"), Some("v4.0.0-rc.3"));
+        assert_eq!(circuit_version_for(SDK_VERSION), Some("v4.0.0-rc.3"));
+        assert_eq!(circuit_version_for("4.0.0-rc.1"), Some("v4.0.0-rc.3"));
+    }
+
+    #[test]
+    fn does_not_map_unknown_releases() {
+        assert_eq!(circuit_version_for("5.0.0"), None);
+        assert_eq!(circuit_version_for("4.10.0"), None);
+        assert_eq!(circuit_version_for("4"), None);
+    }
+
+    #[test]
+    fn checks_proof_circuit_version() {
+        assert_eq!(check_sp1_version(expected_circuit_version()), Ok(()));
+        assert_eq!(
+            check_sp1_version("v3.0.0"),
+            Err(VerifyError::VersionMismatch {
+                proof: "v3.0.0".to_string(),
+                expected: format!("{} (sp1-sdk 4.0, 4.1)", expected_circuit_version()),
+            })
+        );
+    }
+}
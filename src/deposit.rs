@@ -0,0 +1,309 @@
+//! Twine bridge deposit messages in proven account data.
+//!
+//! Deposit accounts of the Twine bridge program start with the 8-byte
+//! [`DEPOSIT_DISCRIMINATOR`], followed by the Borsh encoding of a
+//! `Vec<Deposit>`: a `u32` LE count and fixed-size entries. The bridge
+//! program id is not part of the consensus data, so it is given to
+//! [`DepositDecoder`].
+//!
+//! Accounts that do not match are reported with the reason rather than
+//! dropped. Pyth price accounts, which start with the Pyth magic number but
+//! not the rest of the discriminator, are named as such.
+
+use crate::public_values::{Account, ConsensusPackage, Pubkey};
+use std::error::Error;
+use std::fmt;
+
+/// Discriminator of the bridge program's deposit accounts
+pub const DEPOSIT_DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = [212, 195, 178, 161, 2, 0, 0, 0];
+
+/// First four bytes (LE) of every Pyth oracle account
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+
+/// Length of an account discriminator
+const DISCRIMINATOR_LEN: usize = 8;
+
+/// Encoded length of a [`Deposit`]
+const DEPOSIT_LEN: usize = 32 + 20 + 32 + 8 + 8;
+
+/// A deposit from Solana to the Twine L2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deposit {
+    /// Solana account that made the deposit
+    pub sender: Pubkey,
+    /// L2 address credited with the deposit
+    pub recipient: [u8; 20],
+    /// Mint of the deposited token
+    pub token: Pubkey,
+    /// Amount in the token's base units
+    pub amount: u64,
+    /// Bridge sequence number of the deposit
+    pub nonce: u64,
+}
+
+/// Why an account holds no deposits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepositError {
+    /// The account is not owned by the bridge program
+    WrongOwner { owner: Pubkey },
+    /// The account is a Pyth oracle account
+    PythAccount { account_type: u32 },
+    /// The account data does not start with the deposit account discriminator
+    Discriminator { found: Vec<u8> },
+    /// The account data ends before the deposits it declares
+    Truncated { len: usize, needed: usize },
+    /// The account data continues after the declared deposits
+    TrailingBytes { count: usize },
+}
+
+impl fmt::Display for DepositError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DepositError::WrongOwner { owner } => write!(
+                f,
+                "owned by {}, not the bridge program",
+                bs58::encode(owner).into_string()
+            ),
+            DepositError::PythAccount { account_type } => write!(
+                f,
+                "Pyth oracle account (type {}), not a deposit account",
+                account_type
+            ),
+            DepositError::Discriminator { found } => write!(
+                f,
+                "discriminator {} is not the deposit account discriminator",
+                hex::encode(found)
+            ),
+            DepositError::Truncated { len, needed } => write!(
+                f,
+                "account data is {} bytes but the deposits need {}",
+                len, needed
+            ),
+            DepositError::TrailingBytes { count } => {
+                write!(f, "{} bytes after the last deposit", count)
+            }
+        }
+    }
+}
+
+impl Error for DepositError {}
+
+/// Deposits found in a package, and the accounts that hold none
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepositScan {
+    /// Each deposit with the slot and address of its account
+    pub deposits: Vec<(u64, Pubkey, Deposit)>,
+    /// Each account without deposits, with the reason
+    pub skipped: Vec<(u64, Pubkey, DepositError)>,
+}
+
+/// Decodes deposit accounts of the Twine bridge program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositDecoder {
+    /// Bridge program id; accounts of other owners are skipped
+    pub program_id: Pubkey,
+}
+
+impl DepositDecoder {
+    /// Decode the deposit accounts owned by the given bridge program
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    /// Decode the deposits held by an account
+    pub fn decode(&self, account: &Account) -> Result<Vec<Deposit>, DepositError> {
+        if account.owner != self.program_id {
+            return Err(DepositError::WrongOwner {
+                owner: account.owner,
+            });
+        }
+        let data = &account.data;
+        if !data.starts_with(&DEPOSIT_DISCRIMINATOR) {
+            if let Some(account_type) = pyth_account_type(data) {
+                return Err(DepositError::PythAccount { account_type });
+            }
+            let len = data.len().min(DISCRIMINATOR_LEN);
+            return Err(DepositError::Discriminator {
+                found: data[..len].to_vec(),
+            });
+        }
+
+        let body = &data[DISCRIMINATOR_LEN..];
+        let count = read_u32(body, 0).ok_or(DepositError::Truncated {
+            len: data.len(),
+            needed: DISCRIMINATOR_LEN + 4,
+        })? as usize;
+        // Saturate so a corrupt count on 32-bit targets reads as truncated data
+        let needed = count.saturating_mul(DEPOSIT_LEN).saturating_add(4);
+        if body.len() < needed {
+            return Err(DepositError::Truncated {
+                len: data.len(),
+                needed: needed.saturating_add(DISCRIMINATOR_LEN),
+            });
+        }
+        if body.len() > needed {
+            return Err(DepositError::TrailingBytes {
+                count: body.len() - needed,
+            });
+        }
+
+        Ok(body[4..]
+            .chunks_exact(DEPOSIT_LEN)
+            .map(decode_deposit)
+            .collect())
+    }
+
+    /// Decode every proven account of a package
+    pub fn scan(&self, package: &ConsensusPackage) -> DepositScan {
+        let mut scan = DepositScan::default();
+        for (&slot, proofs) in &package.proofs {
+            for (pubkey, (entry, _)) in proofs {
+                match self.decode(&entry.account) {
+                    Ok(deposits) => scan
+                        .deposits
+                        .extend(deposits.into_iter().map(|deposit| (slot, *pubkey, deposit))),
+                    Err(error) => scan.skipped.push((slot, *pubkey, error)),
+                }
+            }
+        }
+        scan
+    }
+}
+
+/// The account type of a Pyth oracle account, if the data is one
+fn pyth_account_type(data: &[u8]) -> Option<u32> {
+    if read_u32(data, 0)? != PYTH_MAGIC {
+        return None;
+    }
+    read_u32(data, 8)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn decode_deposit(bytes: &[u8]) -> Deposit {
+    let (sender, rest) = bytes.split_at(32);
+    let (recipient, rest) = rest.split_at(20);
+    let (token, rest) = rest.split_at(32);
+    let (amount, nonce) = rest.split_at(8);
+    Deposit {
+        sender: sender.try_into().expect("32-byte sender"),
+        recipient: recipient.try_into().expect("20-byte recipient"),
+        token: token.try_into().expect("32-byte token"),
+        amount: u64::from_le_bytes(amount.try_into().expect("8-byte amount")),
+        nonce: u64::from_le_bytes(nonce.try_into().expect("8-byte nonce")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::load_package;
+    use std::path::Path;
+
+    const PROGRAM_ID: Pubkey = [7; 32];
+
+    fn account(owner: Pubkey, data: Vec<u8>) -> Account {
+        Account {
+            pubkey: [1; 32],
+            lamports: 0,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: 0,
+            slot: 0,
+        }
+    }
+
+    fn deposit(nonce: u64) -> Deposit {
+        Deposit {
+            sender: [2; 32],
+            recipient: [3; 20],
+            token: [4; 32],
+            amount: 500,
+            nonce,
+        }
+    }
+
+    /// A deposit account holding the given deposits
+    fn deposit_data(deposits: &[Deposit]) -> Vec<u8> {
+        let mut data = DEPOSIT_DISCRIMINATOR.to_vec();
+        data.extend((deposits.len() as u32).to_le_bytes());
+        for deposit in deposits {
+            data.extend(deposit.sender);
+            data.extend(deposit.recipient);
+            data.extend(deposit.token);
+            data.extend(deposit.amount.to_le_bytes());
+            data.extend(deposit.nonce.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_deposits() {
+        let deposits = vec![deposit(1), deposit(2)];
+        let account = account(PROGRAM_ID, deposit_data(&deposits));
+
+        assert_eq!(
+            DepositDecoder::new(PROGRAM_ID).decode(&account),
+            Ok(deposits)
+        );
+    }
+
+    #[test]
+    fn checks_owner_before_data() {
+        let mut pyth_data = PYTH_MAGIC.to_le_bytes().to_vec();
+        pyth_data.extend([0; 4]);
+        pyth_data.extend(3u32.to_le_bytes());
+        let decoder = DepositDecoder::new(PROGRAM_ID);
+
+        assert_eq!(
+            decoder.decode(&account([8; 32], pyth_data.clone())),
+            Err(DepositError::WrongOwner { owner: [8; 32] })
+        );
+        assert_eq!(
+            decoder.decode(&account(PROGRAM_ID, pyth_data)),
+            Err(DepositError::PythAccount { account_type: 3 })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_deposit_data() {
+        let decoder = DepositDecoder::new(PROGRAM_ID);
+        let data = deposit_data(&[deposit(1)]);
+
+        assert_eq!(
+            decoder.decode(&account(PROGRAM_ID, data[..data.len() - 1].to_vec())),
+            Err(DepositError::Truncated {
+                len: data.len() - 1,
+                needed: data.len(),
+            })
+        );
+        let mut trailing = data;
+        trailing.push(0);
+        assert_eq!(
+            decoder.decode(&account(PROGRAM_ID, trailing)),
+            Err(DepositError::TrailingBytes { count: 1 })
+        );
+        assert_eq!(
+            decoder.decode(&account(PROGRAM_ID, vec![1, 2, 3])),
+            Err(DepositError::Discriminator {
+                found: vec![1, 2, 3]
+            })
+        );
+    }
+
+    #[test]
+    fn skips_sample_account_of_other_program() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/package_solana.json");
+        let package = load_package(Path::new(path)).unwrap();
+
+        let scan = DepositDecoder::new(PROGRAM_ID).scan(&package);
+        assert!(scan.deposits.is_empty());
+        assert_eq!(scan.skipped.len(), 1);
+        assert!(matches!(scan.skipped[0].2, DepositError::WrongOwner { .. }));
+    }
+}
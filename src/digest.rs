@@ -0,0 +1,72 @@
+use crate::error::VerifyError;
+use crate::native::decimal_to_bytes32;
+use sha2::{Digest, Sha256};
+
+/// Digest of the public values as committed to by the Groth16 and Plonk circuits.
+///
+/// This is the SHA-256 of the public values with the top three bits cleared so
+/// that it fits in a BN254 field element.
+pub fn committed_values_digest(public_values: &[u8]) -> [u8; 32] {
+    let mut digest: [u8; 32] = Sha256::digest(public_values).into();
+    digest[0] &= 0b0001_1111;
+    digest
+}
+
+/// Check that the committed values digest public input matches the public values
+pub fn check_committed_values_digest(
+    public_input: &str,
+    public_values: &[u8],
+) -> Result<(), VerifyError> {
+    let computed = committed_values_digest(public_values);
+
+    match decimal_to_bytes32(public_input) {
+        Some(committed) if committed == computed => Ok(()),
+        committed => Err(VerifyError::PublicValuesDigestMismatch {
+            committed: committed
+                .map(|c| format!("0x{}", hex::encode(c)))
+                .unwrap_or_else(|| format!("'{}' (not a field element)", public_input)),
+            computed: format!("0x{}", hex::encode(computed)),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::{NativeProof, ProofJson};
+    use std::fs;
+
+    /// The committed values digest public input and public values of the sample proof
+    fn sample() -> (String, Vec<u8>) {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/groth16_proof.json");
+        let proof = NativeProof::from_json(&fs::read(path).unwrap()).unwrap();
+        let ProofJson::Groth16(groth16) = &proof.proof else {
+            panic!("sample proof is not Groth16");
+        };
+        (
+            groth16.public_inputs[1].clone(),
+            proof.public_values().to_vec(),
+        )
+    }
+
+    #[test]
+    fn matches_sample_digest() {
+        let (public_input, public_values) = sample();
+        assert_eq!(
+            check_committed_values_digest(&public_input, &public_values),
+            Ok(())
+        );
+        assert_eq!(committed_values_digest(&public_values)[0] & 0b1110_0000, 0);
+    }
+
+    #[test]
+    fn rejects_modified_public_values() {
+        let (public_input, mut public_values) = sample();
+        public_values[0] ^= 0x01;
+
+        assert!(matches!(
+            check_committed_values_digest(&public_input, &public_values),
+            Err(VerifyError::PublicValuesDigestMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::account_delta::AccountProofError;
+use crate::expect::ExpectationMismatch;
+use crate::vote::VoteError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Why a proof could not be verified.
+///
+/// Each variant has a stable [`code`](VerifyError::code) that callers can
+/// match on without parsing messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// A proof or verification key file could not be read
+    IoError(String),
+    /// The proof file is not a valid `SP1ProofWithPublicValues`
+    MalformedProofJson(String),
+    /// The verification key file is not a valid `SP1VerifyingKey`
+    MalformedVkeyJson(String),
+    /// The public values are not a valid consensus output
+    MalformedPublicValues(String),
+    /// The proof was made with an SP1 version this verifier cannot check
+    VersionMismatch { proof: String, expected: String },
+    /// The proof was made for a different program verification key
+    VkeyHashMismatch { expected: String, actual: String },
+    /// The program verification key is not in the allow-list
+    VkeyNotAllowed { vkey_hash: String },
+    /// The public values do not hash to the digest committed in the proof
+    PublicValuesDigestMismatch { committed: String, computed: String },
+    /// A proof point is not on the curve or not in the prime-order subgroup
+    InvalidCurvePoint(String),
+    /// The Groth16 or Plonk pairing check did not hold
+    PairingCheckFailed(String),
+    /// A STARK (core or compressed) proof was rejected
+    InvalidProof(String),
+    /// The proof variant is not supported by this verification path
+    UnsupportedProofKind(String),
+    /// The proof variant is not the one required
+    UnexpectedProofKind { expected: String, actual: String },
+    /// The proof is valid but the program committed that the consensus data did not hold
+    ConsensusRejected { first_slot: u64, last_slot: u64 },
+    /// The proof is valid but commits to unexpected consensus data
+    ConsensusCheckFailed(ExpectationMismatch),
+    /// The proof commits a bank hash that conflicts with one accepted earlier
+    ConflictingBankHash {
+        slot: u64,
+        stored: String,
+        proposed: String,
+    },
+    /// A committed bank hash does not match the slot's bank hash components
+    BankHashMismatch {
+        slot: u64,
+        committed: String,
+        computed: String,
+    },
+    /// A committed account delta proof does not lead to its slot's account delta root
+    InvalidAccountProof { slot: u64, error: AccountProofError },
+    /// A committed validator vote has a bad signature or does not match its instruction
+    InvalidVote { voter: String, error: VoteError },
+    /// An unexpected error inside the verifier
+    Internal(String),
+}
+
+impl VerifyError {
+    /// Stable machine-readable code for the error
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerifyError::IoError(_) => "io_error",
+            VerifyError::MalformedProofJson(_) => "malformed_proof_json",
+            VerifyError::MalformedVkeyJson(_) => "malformed_vkey_json",
+            VerifyError::MalformedPublicValues(_) => "malformed_public_values",
+            VerifyError::VersionMismatch { .. } => "version_mismatch",
+            VerifyError::VkeyHashMismatch { .. } => "vkey_hash_mismatch",
+            VerifyError::VkeyNotAllowed { .. } => "vkey_not_allowed",
+            VerifyError::PublicValuesDigestMismatch { .. } => "public_values_digest_mismatch",
+            VerifyError::InvalidCurvePoint(_) => "invalid_curve_point",
+            VerifyError::PairingCheckFailed(_) => "pairing_check_failed",
+            VerifyError::InvalidProof(_) => "invalid_proof",
+            VerifyError::UnsupportedProofKind(_) => "unsupported_proof_kind",
+            VerifyError::UnexpectedProofKind { .. } => "unexpected_proof_kind",
+            VerifyError::ConsensusRejected { .. } => "consensus_rejected",
+            VerifyError::ConsensusCheckFailed(_) => "consensus_check_failed",
+            VerifyError::ConflictingBankHash { .. } => "conflicting_bank_hash",
+            VerifyError::BankHashMismatch { .. } => "bank_hash_mismatch",
+            VerifyError::InvalidAccountProof { .. } => "invalid_account_proof",
+            VerifyError::InvalidVote { .. } => "invalid_vote",
+            VerifyError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Process exit code for the failure class: 1 invalid proof, 2 malformed
+    /// input, 3 version or vkey mismatch, 4 consensus check failed, 5 internal
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            VerifyError::PublicValuesDigestMismatch { .. }
+            | VerifyError::InvalidCurvePoint(_)
+            | VerifyError::PairingCheckFailed(_)
+            | VerifyError::InvalidProof(_) => 1,
+            VerifyError::IoError(_)
+            | VerifyError::MalformedProofJson(_)
+            | VerifyError::MalformedVkeyJson(_)
+            | VerifyError::MalformedPublicValues(_)
+            | VerifyError::UnsupportedProofKind(_) => 2,
+            VerifyError::VersionMismatch { .. }
+            | VerifyError::VkeyHashMismatch { .. }
+            | VerifyError::VkeyNotAllowed { .. }
+            | VerifyError::UnexpectedProofKind { .. } => 3,
+            VerifyError::ConsensusRejected { .. }
+            | VerifyError::ConsensusCheckFailed(_)
+            | VerifyError::ConflictingBankHash { .. }
+            | VerifyError::BankHashMismatch { .. }
+            | VerifyError::InvalidAccountProof { .. }
+            | VerifyError::InvalidVote { .. } => 4,
+            VerifyError::Internal(_) => 5,
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::IoError(e) => write!(f, "Could not read input: {}", e),
+            VerifyError::MalformedProofJson(e) => write!(f, "Malformed proof JSON: {}", e),
+            VerifyError::MalformedVkeyJson(e) => {
+                write!(f, "Malformed verification key JSON: {}", e)
+            }
+            VerifyError::MalformedPublicValues(e) => {
+                write!(f, "The public values could not be decoded: {}", e)
+            }
+            VerifyError::VersionMismatch { proof, expected } => write!(
+                f,
+                "The proof was generated with SP1 {} but this verifier checks SP1 {} proofs.",
+                proof, expected
+            ),
+            VerifyError::VkeyHashMismatch { expected, actual } => write!(
+                f,
+                "The proof was generated for program vkey {} but {} was expected.",
+                actual, expected
+            ),
+            VerifyError::VkeyNotAllowed { vkey_hash } => write!(
+                f,
+                "The proof was generated by an unknown or retired consensus program version ({}).",
+                vkey_hash
+            ),
+            VerifyError::PublicValuesDigestMismatch { committed, computed } => write!(
+                f,
+                "Public values tampered: the proof commits digest {} but the public values hash to {}.",
+                committed, computed
+            ),
+            VerifyError::InvalidCurvePoint(_) => f.write_str(
+                "The proof contains an invalid curve point (subgroup check failed). This usually means the proof is malformed or corrupted.",
+            ),
+            VerifyError::PairingCheckFailed(_) => f.write_str(
+                "The proof verification failed. The proof may be invalid or not match the verification key.",
+            ),
+            VerifyError::InvalidProof(e) => write!(f, "The proof verification failed: {}", e),
+            VerifyError::UnsupportedProofKind(kind) => {
+                write!(f, "{} proofs are not supported here", kind)
+            }
+            VerifyError::UnexpectedProofKind { expected, actual } => {
+                write!(f, "Expected a {} proof but got a {} proof", expected, actual)
+            }
+            VerifyError::ConsensusRejected {
+                first_slot,
+                last_slot,
+            } => write!(
+                f,
+                "The consensus program committed an invalid result for slots {}..={}.",
+                first_slot, last_slot
+            ),
+            VerifyError::ConsensusCheckFailed(mismatch) => {
+                write!(f, "Consensus check failed: {}", mismatch)
+            }
+            VerifyError::ConflictingBankHash {
+                slot,
+                stored,
+                proposed,
+            } => write!(
+                f,
+                "The proof commits bank hash {} for slot {} but {} was accepted earlier.",
+                proposed, slot, stored
+            ),
+            VerifyError::BankHashMismatch {
+                slot,
+                committed,
+                computed,
+            } => write!(
+                f,
+                "Slot {} commits bank hash {} but its components hash to {}.",
+                slot, committed, computed
+            ),
+            VerifyError::InvalidAccountProof { slot, error } => {
+                write!(f, "Invalid account delta proof in slot {}: {}", slot, error)
+            }
+            VerifyError::InvalidVote { voter, error } => {
+                write!(f, "Invalid vote from validator {}: {}", voter, error)
+            }
+            VerifyError::Internal(e) => write!(f, "Verification error: {}", e),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+impl From<io::Error> for VerifyError {
+    fn from(e: io::Error) -> Self {
+        VerifyError::IoError(e.to_string())
+    }
+}
+
+impl From<ExpectationMismatch> for VerifyError {
+    fn from(mismatch: ExpectationMismatch) -> Self {
+        VerifyError::ConsensusCheckFailed(mismatch)
+    }
+}
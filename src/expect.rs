@@ -0,0 +1,187 @@
+use crate::public_values::{ConsensusOutput, Hash};
+use std::error::Error;
+use std::fmt;
+
+/// Consensus data a proof is required to commit to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsensusExpectations {
+    /// Required first slot of the proven window
+    pub first_slot: Option<u64>,
+    /// Required last slot of the proven window
+    pub last_slot: Option<u64>,
+    /// Required bank hashes for individual slots
+    pub bank_hashes: Vec<(u64, Hash)>,
+}
+
+/// A difference between the committed consensus data and the expectations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectationMismatch {
+    /// The proof starts at a different slot
+    FirstSlot { expected: u64, actual: u64 },
+    /// The proof ends at a different slot
+    LastSlot { expected: u64, actual: u64 },
+    /// The proof does not commit a bank hash for the slot
+    MissingBankHash { slot: u64 },
+    /// The proof commits a different bank hash for the slot
+    BankHash {
+        slot: u64,
+        expected: Hash,
+        actual: Hash,
+    },
+}
+
+impl fmt::Display for ExpectationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectationMismatch::FirstSlot { expected, actual } => write!(
+                f,
+                "expected first slot {} but the proof starts at {}",
+                expected, actual
+            ),
+            ExpectationMismatch::LastSlot { expected, actual } => write!(
+                f,
+                "expected last slot {} but the proof ends at {}",
+                expected, actual
+            ),
+            ExpectationMismatch::MissingBankHash { slot } => {
+                write!(f, "the proof commits no bank hash for slot {}", slot)
+            }
+            ExpectationMismatch::BankHash {
+                slot,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected bank hash {} for slot {} but the proof commits {}",
+                bs58::encode(expected).into_string(),
+                slot,
+                bs58::encode(actual).into_string()
+            ),
+        }
+    }
+}
+
+impl Error for ExpectationMismatch {}
+
+impl ConsensusExpectations {
+    /// Returns true if nothing is expected
+    pub fn is_empty(&self) -> bool {
+        self.first_slot.is_none() && self.last_slot.is_none() && self.bank_hashes.is_empty()
+    }
+
+    /// Check the decoded public values against the expectations
+    pub fn check(&self, output: &ConsensusOutput) -> Result<(), ExpectationMismatch> {
+        let package = &output.package;
+
+        if let Some(expected) = self.first_slot {
+            if package.first_slot != expected {
+                return Err(ExpectationMismatch::FirstSlot {
+                    expected,
+                    actual: package.first_slot,
+                });
+            }
+        }
+
+        if let Some(expected) = self.last_slot {
+            if package.last_slot != expected {
+                return Err(ExpectationMismatch::LastSlot {
+                    expected,
+                    actual: package.last_slot,
+                });
+            }
+        }
+
+        for &(slot, expected) in &self.bank_hashes {
+            match output.bank_hash(slot) {
+                Some(actual) if actual == expected => {}
+                Some(actual) => {
+                    return Err(ExpectationMismatch::BankHash {
+                        slot,
+                        expected,
+                        actual,
+                    })
+                }
+                None => return Err(ExpectationMismatch::MissingBankHash { slot }),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a 32-byte hash given as base58 or hex (with optional `0x` prefix)
+pub fn parse_hash(s: &str) -> Result<Hash, String> {
+    let hex_digits = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = if hex_digits.len() == 64 {
+        hex::decode(hex_digits).map_err(|e| format!("invalid hex hash '{}': {}", s, e))?
+    } else {
+        bs58::decode(s)
+            .into_vec()
+            .map_err(|e| format!("invalid base58 hash '{}': {}", s, e))?
+    };
+
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("hash '{}' is {} bytes, expected 32", s, bytes.len()))
+}
+
+/// Parse a `<slot>=<hash>` bank hash expectation
+pub fn parse_slot_hash(s: &str) -> Result<(u64, Hash), String> {
+    let (slot, hash) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected <slot>=<hash>, got '{}'", s))?;
+    let slot = slot
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid slot '{}': {}", slot, e))?;
+    Ok((slot, parse_hash(hash.trim())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bank hash of the sample slot, in base58 and hex
+    const BASE58: &str = "6eVY82E3Lv3W91S8gjx3ceYYj8tbaB3JYxqgaYCDbur4";
+
+    fn sample_hash() -> Hash {
+        bs58::decode(BASE58).into_vec().unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn parses_base58_and_hex_hashes() {
+        let hash = sample_hash();
+        let hex = hex::encode(hash);
+
+        assert_eq!(parse_hash(BASE58), Ok(hash));
+        assert_eq!(parse_hash(&hex), Ok(hash));
+        assert_eq!(parse_hash(&format!("0x{}", hex)), Ok(hash));
+        assert_eq!(parse_hash(&hex.to_uppercase()), Ok(hash));
+    }
+
+    #[test]
+    fn rejects_hash_of_wrong_length() {
+        let short = bs58::encode([1; 31]).into_string();
+        assert_eq!(
+            parse_hash(&short),
+            Err(format!("hash '{}' is 31 bytes, expected 32", short))
+        );
+        assert!(parse_hash(&format!("0x{}", "zz".repeat(32))).is_err());
+        assert!(parse_hash("").is_err());
+    }
+
+    #[test]
+    fn parses_slot_hash() {
+        assert_eq!(
+            parse_slot_hash(&format!("326251073={}", BASE58)),
+            Ok((326251073, sample_hash()))
+        );
+        assert_eq!(
+            parse_slot_hash(&format!(" 7 = {} ", BASE58)),
+            Ok((7, sample_hash()))
+        );
+        assert!(parse_slot_hash(BASE58).is_err());
+        assert!(parse_slot_hash(&format!("latest={}", BASE58)).is_err());
+        assert!(parse_slot_hash("7=not a hash").is_err());
+    }
+}
@@ -0,0 +1,228 @@
+//! The SP1 Groth16 circuit verifying key and its export to snarkjs JSON.
+//!
+//! `sp1-verifier` embeds the key in gnark's compressed binary format: the
+//! points alpha (G1), beta (G1, G2), gamma (G2), delta (G1, G2) followed by a
+//! big-endian `u32` count and the `K` (gamma_abc) G1 points. Each G1 point is
+//! its x coordinate with two flag bits in the top of the first byte; G2
+//! points store `x.A1 || x.A0`.
+
+use crate::error::VerifyError;
+use crate::native::bytes32_to_decimal;
+use bn::{AffineG1, AffineG2, Fq, Fq2, G2};
+use serde_json::{json, Value};
+use sp1_verifier::GROTH16_VK_BYTES;
+use std::error::Error;
+
+/// Flag bits set on a compressed point
+const MASK: u8 = 0b1100_0000;
+/// Compressed point whose y coordinate is the lexicographically smallest root
+const COMPRESSED_SMALLEST: u8 = 0b1000_0000;
+/// Compressed point whose y coordinate is the lexicographically largest root
+const COMPRESSED_LARGEST: u8 = 0b1100_0000;
+/// Compressed point at infinity
+const COMPRESSED_INFINITY: u8 = 0b0100_0000;
+
+/// (p - 1) / 2 for the BN254 base field, big-endian
+const HALF_MODULUS: [u8; 32] = [
+    0x18, 0x32, 0x27, 0x39, 0x70, 0x98, 0xd0, 0x14, 0xdc, 0x28, 0x22, 0xdb, 0x40, 0xc0, 0xac, 0x2e,
+    0xcb, 0xc0, 0xb5, 0x48, 0xb4, 0x38, 0xe5, 0x46, 0x9e, 0x10, 0x46, 0x0b, 0x6c, 0x3e, 0x7e, 0xa3,
+];
+
+/// A Groth16 verifying key over BN254
+#[derive(Clone)]
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: AffineG1,
+    pub beta_g2: AffineG2,
+    pub gamma_g2: AffineG2,
+    pub delta_g2: AffineG2,
+    /// Points combined with the public inputs (`gamma_abc_g1`)
+    pub k: Vec<AffineG1>,
+}
+
+fn fq_to_bytes(fq: Fq) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    fq.to_big_endian(&mut bytes)
+        .expect("32 bytes always hold a field element");
+    bytes
+}
+
+fn fq_is_largest(fq: Fq) -> bool {
+    fq_to_bytes(fq) > HALF_MODULUS
+}
+
+fn fq2_is_largest(fq2: Fq2) -> bool {
+    if fq2.imaginary().is_zero() {
+        fq_is_largest(fq2.real())
+    } else {
+        fq_is_largest(fq2.imaginary())
+    }
+}
+
+fn flags(buf: &[u8]) -> Result<u8, Box<dyn Error>> {
+    match buf[0] & MASK {
+        COMPRESSED_INFINITY => Err("point at infinity in verifying key".into()),
+        flag @ (COMPRESSED_SMALLEST | COMPRESSED_LARGEST) => Ok(flag),
+        _ => Err("verifying key point is not compressed".into()),
+    }
+}
+
+fn fq_from_bytes(buf: &[u8]) -> Result<Fq, Box<dyn Error>> {
+    Fq::from_slice(buf).map_err(|e| format!("invalid field element: {:?}", e).into())
+}
+
+fn compressed_g1(buf: &[u8]) -> Result<AffineG1, Box<dyn Error>> {
+    let flag = flags(buf)?;
+
+    let mut x_bytes = [0u8; 32];
+    x_bytes.copy_from_slice(&buf[..32]);
+    x_bytes[0] &= !MASK;
+    let x = fq_from_bytes(&x_bytes)?;
+
+    let y_squared = x * x * x + Fq::from_str("3").expect("3 is a field element");
+    let mut y = y_squared.sqrt().ok_or("G1 point is not on the curve")?;
+    if fq_is_largest(y) != (flag == COMPRESSED_LARGEST) {
+        y = -y;
+    }
+
+    AffineG1::new(x, y).map_err(|e| format!("invalid G1 point: {:?}", e).into())
+}
+
+fn compressed_g2(buf: &[u8]) -> Result<AffineG2, Box<dyn Error>> {
+    let flag = flags(buf)?;
+
+    let mut x1_bytes = [0u8; 32];
+    x1_bytes.copy_from_slice(&buf[..32]);
+    x1_bytes[0] &= !MASK;
+    let x = Fq2::new(fq_from_bytes(&buf[32..64])?, fq_from_bytes(&x1_bytes)?);
+
+    let y_squared = x * x * x + G2::b();
+    let mut y = y_squared.sqrt().ok_or("G2 point is not on the curve")?;
+    if fq2_is_largest(y) != (flag == COMPRESSED_LARGEST) {
+        y = -y;
+    }
+
+    AffineG2::new(x, y).map_err(|e| format!("invalid G2 point: {:?}", e).into())
+}
+
+/// Check that the points of a Solidity-encoded Groth16 proof are valid.
+///
+/// The encoding is `A.x, A.y, B.x.A1, B.x.A0, B.y.A1, B.y.A0, C.x, C.y` as
+/// 32-byte big-endian words. Each point must be on the curve and in the
+/// prime-order subgroup.
+pub fn check_proof_points(encoded_proof: &[u8]) -> Result<(), VerifyError> {
+    if encoded_proof.len() != 256 {
+        return Err(VerifyError::MalformedProofJson(format!(
+            "encoded proof is {} bytes, expected 256",
+            encoded_proof.len()
+        )));
+    }
+
+    let word = |i: usize| {
+        Fq::from_slice(&encoded_proof[i * 32..(i + 1) * 32]).map_err(|e| {
+            VerifyError::InvalidCurvePoint(format!("word {} is not a field element: {:?}", i, e))
+        })
+    };
+    let invalid = |name: &str, e| VerifyError::InvalidCurvePoint(format!("{}: {:?}", name, e));
+
+    AffineG1::new(word(0)?, word(1)?).map_err(|e| invalid("A", e))?;
+    AffineG2::new(Fq2::new(word(3)?, word(2)?), Fq2::new(word(5)?, word(4)?))
+        .map_err(|e| invalid("B", e))?;
+    AffineG1::new(word(6)?, word(7)?).map_err(|e| invalid("C", e))?;
+
+    Ok(())
+}
+
+fn decimal(fq: Fq) -> String {
+    bytes32_to_decimal(&fq_to_bytes(fq))
+}
+
+fn g1_json(point: &AffineG1) -> Value {
+    json!([decimal(point.x()), decimal(point.y()), "1"])
+}
+
+fn g2_json(point: &AffineG2) -> Value {
+    let (x, y) = (point.x(), point.y());
+    json!([
+        [decimal(x.real()), decimal(x.imaginary())],
+        [decimal(y.real()), decimal(y.imaginary())],
+        ["1", "0"]
+    ])
+}
+
+impl Groth16VerifyingKey {
+    /// Parse a verifying key in gnark's compressed binary format
+    pub fn from_gnark_bytes(buf: &[u8]) -> Result<Self, Box<dyn Error>> {
+        if buf.len() < 292 {
+            return Err(format!(
+                "verifying key is {} bytes, expected at least 292",
+                buf.len()
+            )
+            .into());
+        }
+
+        let num_k = u32::from_be_bytes([buf[288], buf[289], buf[290], buf[291]]) as usize;
+        let k_bytes = &buf[292..];
+        if k_bytes.len() < num_k * 32 {
+            return Err(format!("verifying key is truncated, expected {} K points", num_k).into());
+        }
+
+        Ok(Self {
+            alpha_g1: compressed_g1(&buf[..32])?,
+            beta_g2: compressed_g2(&buf[64..128])?,
+            gamma_g2: compressed_g2(&buf[128..192])?,
+            delta_g2: compressed_g2(&buf[224..288])?,
+            k: k_bytes
+                .chunks(32)
+                .take(num_k)
+                .map(compressed_g1)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The SP1 Groth16 circuit verifying key embedded in this build
+    pub fn embedded() -> Result<Self, Box<dyn Error>> {
+        Self::from_gnark_bytes(&GROTH16_VK_BYTES)
+    }
+
+    /// Export the key in snarkjs `verification_key.json` format
+    pub fn to_snarkjs_json(&self) -> Value {
+        json!({
+            "protocol": "groth16",
+            "curve": "bn128",
+            "nPublic": self.k.len().saturating_sub(1),
+            "vk_alpha_1": g1_json(&self.alpha_g1),
+            "vk_beta_2": g2_json(&self.beta_g2),
+            "vk_gamma_2": g2_json(&self.gamma_g2),
+            "vk_delta_2": g2_json(&self.delta_g2),
+            "IC": self.k.iter().map(g1_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_embedded_key() {
+        let vk = Groth16VerifyingKey::embedded().unwrap();
+
+        // One K point for the constant term and one per public input:
+        // the program vkey hash and the committed values digest
+        assert_eq!(vk.k.len(), 3);
+        assert_eq!(vk.to_snarkjs_json()["nPublic"], 2);
+    }
+
+    #[test]
+    fn rejects_truncated_key() {
+        assert!(Groth16VerifyingKey::from_gnark_bytes(&[0; 291]).is_err());
+
+        let mut buf = [0u8; 292 + 32];
+        buf[288..292].copy_from_slice(&2u32.to_be_bytes());
+        let error = Groth16VerifyingKey::from_gnark_bytes(&buf).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "verifying key is truncated, expected 2 K points"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::error::VerifyError;
+use crate::report::InputFile;
+use sevenz_rust::{Password, SevenZReader};
+use sp1_sdk::{SP1ProofWithPublicValues, SP1VerifyingKey};
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+const SEVEN_ZIP_MAGIC: &[u8] = &[0x37, 0x7a, 0xbc, 0xaf, 0x27, 0x1c];
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Archives nested deeper than this are rejected
+const MAX_NESTING: usize = 4;
+
+/// Largest decompressed input accepted by default
+pub const DEFAULT_MAX_DECOMPRESSED_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Parse a proof from its JSON representation
+pub fn parse_proof_json(bytes: &[u8]) -> Result<SP1ProofWithPublicValues, VerifyError> {
+    serde_json::from_slice(bytes).map_err(|e| VerifyError::MalformedProofJson(e.to_string()))
+}
+
+/// Parse a verification key from its JSON representation
+pub fn parse_vkey_json(bytes: &[u8]) -> Result<SP1VerifyingKey, VerifyError> {
+    serde_json::from_slice(bytes).map_err(|e| VerifyError::MalformedVkeyJson(e.to_string()))
+}
+
+/// Parse a proof saved as JSON or with bincode (`SP1ProofWithPublicValues::save`)
+pub fn parse_proof(bytes: &[u8]) -> Result<SP1ProofWithPublicValues, VerifyError> {
+    if is_json(bytes) {
+        return parse_proof_json(bytes);
+    }
+    bincode::deserialize(bytes).map_err(|e| {
+        VerifyError::MalformedProofJson(format!("neither a JSON nor a bincode proof: {}", e))
+    })
+}
+
+fn is_json(bytes: &[u8]) -> bool {
+    bytes
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| *byte == b'{')
+}
+
+/// Serialization of a proof file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofFormat {
+    Json,
+    /// SP1's bincode format, as written by `SP1ProofWithPublicValues::save`
+    Binary,
+}
+
+impl ProofFormat {
+    /// Format implied by a file name: `.json` is JSON, anything else binary
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ProofFormat::Json,
+            _ => ProofFormat::Binary,
+        }
+    }
+}
+
+/// Serialize a proof in the given format
+pub fn encode_proof(
+    proof: &SP1ProofWithPublicValues,
+    format: ProofFormat,
+) -> Result<Vec<u8>, VerifyError> {
+    match format {
+        ProofFormat::Json => {
+            serde_json::to_vec(proof).map_err(|e| VerifyError::Internal(e.to_string()))
+        }
+        ProofFormat::Binary => {
+            bincode::serialize(proof).map_err(|e| VerifyError::Internal(e.to_string()))
+        }
+    }
+}
+
+/// Read a file, or standard input for `-`
+pub fn read_source(path: &Path) -> Result<Vec<u8>, VerifyError> {
+    if path == Path::new("-") {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        return Ok(bytes);
+    }
+    Ok(fs::read(path)?)
+}
+
+/// Undo gzip, zstd and 7z compression, detected by magic bytes.
+///
+/// A 7z archive is expected to hold a single file; the first file is used.
+/// Each level may decompress to at most `limit` bytes, so a small
+/// compressed input cannot exhaust memory.
+pub fn decompress(bytes: Vec<u8>, limit: u64) -> Result<Vec<u8>, VerifyError> {
+    let mut bytes = bytes;
+    for _ in 0..MAX_NESTING {
+        bytes = if bytes.starts_with(SEVEN_ZIP_MAGIC) {
+            extract_first_file(bytes, limit)?
+        } else if bytes.starts_with(GZIP_MAGIC) {
+            read_limited(flate2::read::GzDecoder::new(&bytes[..]), limit)?
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            read_limited(zstd::stream::read::Decoder::new(&bytes[..])?, limit)?
+        } else {
+            return Ok(bytes);
+        };
+    }
+    Err(VerifyError::IoError(format!(
+        "input is compressed more than {} levels deep",
+        MAX_NESTING
+    )))
+}
+
+/// Read a decompressing reader to the end, failing once it yields more than `limit` bytes
+fn read_limited(reader: impl Read, limit: u64) -> Result<Vec<u8>, VerifyError> {
+    let mut bytes = Vec::new();
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    check_decompressed_len(&bytes, limit)?;
+    Ok(bytes)
+}
+
+fn check_decompressed_len(bytes: &[u8], limit: u64) -> Result<(), VerifyError> {
+    if bytes.len() as u64 > limit {
+        return Err(VerifyError::IoError(format!(
+            "input decompresses to more than {} bytes",
+            limit
+        )));
+    }
+    Ok(())
+}
+
+fn extract_first_file(archive: Vec<u8>, limit: u64) -> Result<Vec<u8>, VerifyError> {
+    let len = archive.len() as u64;
+    let seven_zip_error = |e: sevenz_rust::Error| VerifyError::IoError(format!("7z: {}", e));
+
+    let mut reader =
+        SevenZReader::new(Cursor::new(archive), len, Password::empty()).map_err(seven_zip_error)?;
+    let mut contents = None;
+    reader
+        .for_each_entries(|entry, entry_reader| {
+            if entry.is_directory() {
+                return Ok(true);
+            }
+            let mut bytes = Vec::new();
+            entry_reader
+                .take(limit.saturating_add(1))
+                .read_to_end(&mut bytes)?;
+            contents = Some(bytes);
+            Ok(false)
+        })
+        .map_err(seven_zip_error)?;
+
+    let contents =
+        contents.ok_or_else(|| VerifyError::IoError("7z archive contains no file".to_string()))?;
+    check_decompressed_len(&contents, limit)?;
+    Ok(contents)
+}
+
+/// Read an input file or stdin, returning its description and decompressed contents.
+///
+/// The SHA-256 in the description is of the bytes as read, before decompression.
+pub fn read_input(path: &Path) -> Result<(InputFile, Vec<u8>), VerifyError> {
+    let bytes = read_source(path)?;
+    let file = InputFile::new(path.display().to_string(), &bytes);
+    Ok((file, decompress(bytes, DEFAULT_MAX_DECOMPRESSED_BYTES)?))
+}
+
+/// Load a proof file from disk, or stdin for `-`
+pub fn load_proof(path: &Path) -> Result<SP1ProofWithPublicValues, VerifyError> {
+    let (_, bytes) = read_input(path)?;
+    parse_proof(&bytes)
+}
+
+/// Load a verification key JSON file from disk, or stdin for `-`
+pub fn load_vkey(path: &Path) -> Result<SP1VerifyingKey, VerifyError> {
+    let (_, bytes) = read_input(path)?;
+    parse_vkey_json(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decompresses_within_limit() {
+        let bytes = vec![b'{'; 1000];
+        assert_eq!(decompress(gzip(&bytes), 1000).unwrap(), bytes);
+        let zstd = zstd::stream::encode_all(&bytes[..], 0).unwrap();
+        assert_eq!(decompress(zstd, 1000).unwrap(), bytes);
+    }
+
+    #[test]
+    fn rejects_input_decompressing_past_limit() {
+        let bomb = gzip(&vec![0; 1024 * 1024]);
+        let error = decompress(bomb, 1000).unwrap_err();
+        assert_eq!(error.code(), "io_error");
+
+        let nested = zstd::stream::encode_all(&gzip(&vec![0; 1024 * 1024])[..], 0).unwrap();
+        assert!(decompress(nested, 1000).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::proof_kind::ProofKind;
+use crate::public_values::decode_public_values;
+use sp1_sdk::{SP1Proof, SP1ProofWithPublicValues};
+
+/// Summary of a proof file, gathered without verifying it
+#[derive(Debug, Clone)]
+pub struct ProofSummary {
+    /// The proof variant
+    pub kind: ProofKind,
+    /// SP1 version recorded in the proof
+    pub sp1_version: String,
+    /// Hash of the BN254 circuit verifying key, for Groth16 and Plonk proofs
+    pub circuit_vkey_hash: Option<String>,
+    /// Program vkey hash and committed values digest, for Groth16 and Plonk proofs
+    pub public_inputs: Option<[String; 2]>,
+    /// Length of the committed public values in bytes
+    pub public_values_len: usize,
+    /// Slot range decoded from the public values, or why decoding failed
+    pub slot_range: Result<(u64, u64), String>,
+}
+
+/// Summarize a proof without verifying it
+pub fn summarize_proof(proof: &SP1ProofWithPublicValues) -> ProofSummary {
+    let (circuit_vkey_hash, public_inputs) = match &proof.proof {
+        SP1Proof::Groth16(p) => (
+            Some(hex::encode(p.groth16_vkey_hash)),
+            Some(p.public_inputs.clone()),
+        ),
+        SP1Proof::Plonk(p) => (
+            Some(hex::encode(p.plonk_vkey_hash)),
+            Some(p.public_inputs.clone()),
+        ),
+        SP1Proof::Compressed(_) | SP1Proof::Core(_) => (None, None),
+    };
+
+    let public_values = proof.public_values.as_slice();
+    let slot_range = decode_public_values(public_values)
+        .map(|output| (output.package.first_slot, output.package.last_slot))
+        .map_err(|e| e.to_string());
+
+    ProofSummary {
+        kind: ProofKind::of(&proof.proof),
+        sp1_version: proof.sp1_version.clone(),
+        circuit_vkey_hash,
+        public_inputs,
+        public_values_len: public_values.len(),
+        slot_range,
+    }
+}
@@ -0,0 +1,71 @@
+//! Library for verifying Solana consensus proofs produced by the Twine SP1 prover.
+//!
+//! The [`Verifier`] type wraps the SP1 prover client and turns the result of
+//! `client.verify` into a structured [`VerificationOutcome`]. Nothing in this
+//! crate prints; callers decide how to report the outcome.
+//!
+//! Everything that needs `sp1-sdk` is behind the default `prover` feature.
+//! Without it the crate still provides the [`native`] Groth16 verifier and
+//! the public values decoder, which build for constrained targets and wasm.
+
+pub mod account_delta;
+pub mod allowlist;
+pub mod bank_hash;
+#[cfg(feature = "prover")]
+pub mod batch;
+pub mod chain;
+pub mod compat;
+pub mod deposit;
+pub mod digest;
+pub mod error;
+pub mod expect;
+pub mod groth16_vk;
+#[cfg(feature = "prover")]
+pub mod input;
+#[cfg(feature = "prover")]
+pub mod inspect;
+pub mod message;
+pub mod native;
+pub mod outcome;
+pub mod package;
+pub mod proof_kind;
+pub mod public_values;
+pub mod report;
+#[cfg(feature = "prover")]
+pub mod server;
+pub mod short_vec;
+pub mod store;
+#[cfg(feature = "prover")]
+pub mod verifier;
+#[cfg(feature = "prover")]
+pub mod vkey;
+pub mod vote;
+
+pub use account_delta::{hash_account, verify_account_proofs, AccountProofError};
+pub use allowlist::{AllowedVkey, VkeyAllowList};
+pub use bank_hash::{check_bank_hash, compute_bank_hash, BankHashExtras};
+#[cfg(feature = "prover")]
+pub use batch::{BatchEntry, BatchManifest, BatchResult, ChainResult};
+pub use chain::{ChainBreak, ChainChecker};
+pub use deposit::{Deposit, DepositDecoder, DepositScan};
+pub use error::VerifyError;
+pub use expect::{ConsensusExpectations, ExpectationMismatch};
+#[cfg(feature = "prover")]
+pub use input::{
+    encode_proof, load_proof, load_vkey, parse_proof, parse_proof_json, parse_vkey_json,
+    read_input, ProofFormat,
+};
+#[cfg(feature = "prover")]
+pub use inspect::{summarize_proof, ProofSummary};
+pub use native::{NativeProof, NativeVerifier};
+pub use outcome::{CheckResult, CheckStatus, VerificationOutcome, VerificationStatus};
+pub use package::{check_package, diff_packages, PackageCheck, PackageDifference, PackageIssue};
+pub use proof_kind::ProofKind;
+pub use public_values::{decode_public_values, ConsensusOutput, ConsensusPackage};
+pub use report::VerificationReport;
+#[cfg(feature = "prover")]
+pub use sp1_sdk::{SP1ProofWithPublicValues, SP1VerifyingKey};
+pub use store::{SlotStore, StoreRecord};
+#[cfg(feature = "prover")]
+pub use verifier::Verifier;
+pub use vote::{check_vote, check_votes, TowerSync, VoteError};
@@ -1,124 +1,1221 @@
-use clap::Parser;
-use serde_json;
-use sp1_sdk::ProverClient;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::json;
+use sp1_sdk::HashableKey;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fs;
-use std::panic::{self, AssertUnwindSafe};
+use std::io::{self, Write};
 use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
+use twine_solana_consensus_proof_verifier::allowlist::{parse_allowed_vkey, AllowedVkey};
+use twine_solana_consensus_proof_verifier::batch::{BatchEntry, BatchManifest, BatchResult};
+use twine_solana_consensus_proof_verifier::chain::{parse_slot_range, ChainChecker};
+use twine_solana_consensus_proof_verifier::compat::check_sp1_version;
+use twine_solana_consensus_proof_verifier::deposit::DepositDecoder;
+use twine_solana_consensus_proof_verifier::expect::parse_slot_hash;
+use twine_solana_consensus_proof_verifier::groth16_vk::Groth16VerifyingKey;
+use twine_solana_consensus_proof_verifier::input::DEFAULT_MAX_DECOMPRESSED_BYTES;
+use twine_solana_consensus_proof_verifier::native::{NativeProof, NativeVerifier};
+use twine_solana_consensus_proof_verifier::package::load_package;
+use twine_solana_consensus_proof_verifier::public_values::{Hash, Pubkey};
+use twine_solana_consensus_proof_verifier::report::{InputFile, ReportError, Timings};
+use twine_solana_consensus_proof_verifier::server::VerifyServer;
+use twine_solana_consensus_proof_verifier::vkey::describe_vkey;
+use twine_solana_consensus_proof_verifier::{
+    check_package, decode_public_values, diff_packages, encode_proof, load_proof, load_vkey,
+    parse_proof, parse_vkey_json, read_input, summarize_proof, CheckResult, CheckStatus,
+    ConsensusExpectations, ConsensusOutput, ConsensusPackage, ProofFormat, ProofKind, SlotStore,
+    StoreRecord, VerificationOutcome, VerificationReport, VerificationStatus, Verifier,
+    VerifyError, VkeyAllowList,
+};
 
 /// Command line arguments for the Twine Solana consensus proof verifier
 #[derive(Parser, Debug)]
 #[clap(author, version, about = "Twine Solana Consensus Proof Verifier")]
 struct Args {
-    /// Path to the proof JSON file
+    /// Path to the proof file (JSON or bincode, optionally .gz/.zst/.7z), or - for stdin
     #[clap(short, long, default_value = "data/groth16_proof.json")]
     proof_path: String,
 
     /// Path to the verification key JSON file
     #[clap(short, long, default_value = "data/vkey.json")]
     vkey_path: String,
+
+    /// Require the proof to start at this slot
+    #[clap(long)]
+    expect_first_slot: Option<u64>,
+
+    /// Require the proof to end at this slot
+    #[clap(long)]
+    expect_last_slot: Option<u64>,
+
+    /// Require the proof to commit this bank hash, as <slot>=<base58|hex> (repeatable)
+    #[clap(long, value_parser = parse_slot_hash)]
+    expect_bank_hash: Vec<(u64, Hash)>,
+
+    /// Recompute every committed bank hash from its components. Slots whose
+    /// bank hash mixes in the epoch accounts hash or lattice hash fail this check.
+    #[clap(long)]
+    check_bank_hashes: bool,
+
+    /// Path to a JSON allow-list of known-good program vkey hashes
+    #[clap(long)]
+    allowed_vkeys: Option<String>,
+
+    /// Allow a program vkey hash, as <hash>[=<label>] (repeatable)
+    #[clap(long, value_parser = parse_allowed_vkey)]
+    allowed_vkey: Vec<AllowedVkey>,
+
+    /// Verify a Groth16 proof with native pairing checks instead of the SP1 prover client
+    #[clap(long)]
+    native: bool,
+
+    /// Record verified slots in this light-client store and reject conflicting bank hashes
+    #[clap(long)]
+    store: Option<String>,
+
+    /// Proof variant to accept; other variants are rejected
+    #[clap(long, value_enum, default_value = "any")]
+    proof_kind: ProofKindArg,
+
+    /// Output format of the verification result
+    #[clap(long, value_enum, default_value = "text")]
+    output: OutputFormat,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
-/// Extract a user-friendly error message from a panic payload
-fn extract_error_message(panic_message: &str) -> String {
-    // Look for common error patterns in SP1 panic messages
-    if panic_message.contains("invalid point: subgroup check failed") {
-        return "The proof contains an invalid curve point (subgroup check failed). This usually means the proof is malformed or corrupted.".to_string();
-    } else if panic_message.contains("failed to verify proof") {
-        return "The proof verification failed. The proof may be invalid or not match the verification key.".to_string();
-    } else {
-        // Return a simplified version of the original error
-        let error_lines: Vec<&str> = panic_message.lines().collect();
-        if !error_lines.is_empty() {
-            return format!("Verification error: {}", error_lines[0]);
-        } else {
-            return "Unknown verification error occurred".to_string();
+/// How the verification result is printed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable progress and result lines
+    Text,
+    /// A single JSON report
+    Json,
+}
+
+/// Proof serialization selected on the command line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ProofFormatArg {
+    Json,
+    Binary,
+}
+
+impl From<ProofFormatArg> for ProofFormat {
+    fn from(format: ProofFormatArg) -> Self {
+        match format {
+            ProofFormatArg::Json => ProofFormat::Json,
+            ProofFormatArg::Binary => ProofFormat::Binary,
+        }
+    }
+}
+
+/// Proof variant selected on the command line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ProofKindArg {
+    Groth16,
+    Plonk,
+    Compressed,
+    Core,
+    /// Accept every variant
+    Any,
+}
+
+impl ProofKindArg {
+    /// The variant proofs must have, if one is required
+    fn required(self) -> Option<ProofKind> {
+        match self {
+            ProofKindArg::Groth16 => Some(ProofKind::Groth16),
+            ProofKindArg::Plonk => Some(ProofKind::Plonk),
+            ProofKindArg::Compressed => Some(ProofKind::Compressed),
+            ProofKindArg::Core => Some(ProofKind::Core),
+            ProofKindArg::Any => None,
         }
     }
 }
 
-/// Verify a Solana consensus proof using SP1
-fn verify_proof(proof_path: &Path, vkey_path: &Path) -> Result<bool, Box<dyn Error>> {
+/// Subcommands other than the default verification
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the contents of a proof file without verifying it
+    Inspect {
+        /// Path to the proof file, or - for stdin
+        #[clap(short, long, default_value = "data/groth16_proof.json")]
+        proof_path: String,
+    },
+
+    /// Convert a proof between JSON and SP1's binary format
+    Convert {
+        /// Proof file to read, in any supported input format, or - for stdin
+        input: String,
+
+        /// Path to write the converted proof to, or - for stdout
+        output: String,
+
+        /// Output format; defaults to JSON for a `.json` output path and binary otherwise
+        #[clap(long, value_enum)]
+        to: Option<ProofFormatArg>,
+    },
+
+    /// Print verification key hashes and export the Groth16 verifying key
+    Vkey {
+        /// Path to the verification key JSON file
+        #[clap(short, long, default_value = "data/vkey.json")]
+        vkey_path: String,
+
+        /// Write the SP1 Groth16 circuit verifying key as snarkjs JSON to this path
+        #[clap(long)]
+        export_groth16: Option<String>,
+    },
+
+    /// Verify many proofs in parallel with one prover client
+    VerifyBatch {
+        /// Glob pattern or directory of proof JSON files
+        #[clap(
+            long,
+            required_unless_present = "manifest",
+            conflicts_with = "manifest"
+        )]
+        proofs: Option<String>,
+
+        /// Path to the verification key JSON file for --proofs
+        #[clap(short, long, default_value = "data/vkey.json")]
+        vkey_path: String,
+
+        /// Path to a JSON manifest mapping proof files to verification key files
+        #[clap(long)]
+        manifest: Option<String>,
+    },
+
+    /// Verify an ordered sequence of proofs and check that they form a continuous chain
+    VerifyChain {
+        /// Path to the verification key JSON file
+        #[clap(short, long, default_value = "data/vkey.json")]
+        vkey_path: String,
+
+        /// Allow the slots <first>-<last> to be left uncovered (repeatable)
+        #[clap(long, value_parser = parse_slot_range)]
+        allow_gap: Vec<(u64, u64)>,
+
+        /// Proof JSON files in slot order
+        #[clap(required = true)]
+        proofs: Vec<String>,
+    },
+
+    /// Re-check the consensus constraints natively against a witness package
+    CheckPackage {
+        /// Path to the witness package JSON file
+        #[clap(long, default_value = "data/package_solana.json")]
+        package: String,
+    },
+
+    /// Compare the consensus data committed by a proof with a witness package
+    MatchPackage {
+        /// Path to the proof file, or - for stdin
+        #[clap(short, long, default_value = "data/groth16_proof.json")]
+        proof_path: String,
+
+        /// Path to the witness package JSON file
+        #[clap(long, default_value = "data/package_solana.json")]
+        package: String,
+    },
+
+    /// Verify the proof and list the Twine deposits in its proven accounts
+    Deposits {
+        /// Bridge program id (base58); accounts owned by other programs are skipped
+        #[clap(long, value_parser = parse_pubkey)]
+        program_id: Pubkey,
+    },
+
+    /// Serve verification over HTTP
+    Serve {
+        /// Address to listen on
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        bind: String,
+
+        /// Path to the default verification key JSON file
+        #[clap(short, long, default_value = "data/vkey.json")]
+        vkey_path: String,
+
+        /// Additional verification key, as <id>=<path> (repeatable)
+        #[clap(long, value_parser = parse_vkey_id)]
+        vkey: Vec<(String, String)>,
+
+        /// Number of requests verified concurrently
+        #[clap(long, default_value_t = 4)]
+        threads: usize,
+
+        /// Reject request bodies that decompress to more than this many bytes
+        #[clap(long, default_value_t = DEFAULT_MAX_DECOMPRESSED_BYTES)]
+        max_decompressed_bytes: u64,
+    },
+}
+
+/// Parse a base58 public key
+fn parse_pubkey(s: &str) -> Result<Pubkey, String> {
+    let bytes = bs58::decode(s)
+        .into_vec()
+        .map_err(|e| format!("invalid base58 '{}': {}", s, e))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("public key is {} bytes, expected 32", bytes.len()))
+}
+
+/// Parse a verification key given as `<id>=<path>`
+fn parse_vkey_id(s: &str) -> Result<(String, String), String> {
+    let (id, path) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected <id>=<path>, got '{}'", s))?;
+    Ok((id.to_string(), path.to_string()))
+}
+
+/// Print a summary of a proof file without verifying it
+fn inspect_proof(proof_path: &Path) -> Result<(), Box<dyn Error>> {
     println!("Loading proof from: {}", proof_path.display());
+    let proof = load_proof(proof_path)?;
+    let summary = summarize_proof(&proof);
+
+    println!("Proof kind: {}", summary.kind);
+    println!("SP1 version: {}", summary.sp1_version);
+    if let Err(e) = check_sp1_version(&summary.sp1_version) {
+        println!("Warning: {}", e);
+    }
+    if let Some(hash) = &summary.circuit_vkey_hash {
+        println!("Circuit vkey hash: {}", hash);
+    }
+    if let Some([vkey_hash, committed_values_digest]) = &summary.public_inputs {
+        println!("Public input 0 (program vkey hash): {}", vkey_hash);
+        println!(
+            "Public input 1 (committed values digest): {}",
+            committed_values_digest
+        );
+    }
+    println!("Public values: {} bytes", summary.public_values_len);
+    match &summary.slot_range {
+        Ok((first_slot, last_slot)) => println!("Slot range: {}..={}", first_slot, last_slot),
+        Err(e) => println!("Slot range: could not decode public values ({})", e),
+    }
 
-    // Load the proof file
-    let proof_json = fs::read_to_string(proof_path)?;
-    let proof = serde_json::from_str(&proof_json)?;
+    Ok(())
+}
 
+/// Convert a proof to JSON or binary, checking that its public values survive the round trip.
+///
+/// Progress goes to stderr when the proof is written to stdout.
+fn convert_proof(
+    input: &Path,
+    output: &Path,
+    to: Option<ProofFormat>,
+) -> Result<(), Box<dyn Error>> {
+    let to_stdout = output == Path::new("-");
+    let progress = |line: String| {
+        if to_stdout {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    };
+
+    progress(format!("Loading proof from: {}", input.display()));
+    let (_, bytes) = read_input(input)?;
+    let proof = parse_proof(&bytes)?;
+
+    let format = to.unwrap_or_else(|| ProofFormat::from_path(output));
+    let encoded = encode_proof(&proof, format)?;
+    let converted = parse_proof(&encoded)?;
+    if converted.public_values.as_slice() != proof.public_values.as_slice() {
+        return Err(
+            VerifyError::Internal("the public values changed in conversion".to_string()).into(),
+        );
+    }
+
+    if to_stdout {
+        io::stdout().write_all(&encoded)?;
+    } else {
+        fs::write(output, &encoded)?;
+    }
+    let name = match format {
+        ProofFormat::Json => "JSON",
+        ProofFormat::Binary => "binary",
+    };
+    progress(format!(
+        "Wrote {} proof ({} bytes) to: {}",
+        name,
+        encoded.len(),
+        output.display()
+    ));
+    Ok(())
+}
+
+/// Print the hashes and chips of a verification key
+fn show_vkey(vkey_path: &Path, export_groth16: Option<&Path>) -> Result<(), Box<dyn Error>> {
     println!("Loading verification key from: {}", vkey_path.display());
+    let (_, bytes) = read_input(vkey_path)?;
+    let info = describe_vkey(&bytes)?;
+
+    println!("Vkey hash (bytes32): {}", info.bytes32);
+    println!("Vkey hash (BN254 field): {}", info.hash_bn254);
+    println!("PC start: {:#x}", info.pc_start);
+    println!("Chips:");
+    for chip in &info.chips {
+        println!(
+            "  {}: log_n {}, width {}, height {}",
+            chip.name, chip.log_n, chip.width, chip.height
+        );
+    }
+
+    if let Some(path) = export_groth16 {
+        let vk = Groth16VerifyingKey::embedded()?;
+        fs::write(path, serde_json::to_string_pretty(&vk.to_snarkjs_json())?)?;
+        println!(
+            "Exported Groth16 verifying key with {} gamma_abc_g1 points to: {}",
+            vk.k.len(),
+            path.display()
+        );
+    }
+
+    Ok(())
+}
 
-    // Load the verification key file
-    let vkey_json = fs::read_to_string(vkey_path)?;
-    let vk = serde_json::from_str(&vkey_json)?;
+/// Print the slots, bank hashes and accounts committed by a proof
+fn print_consensus_summary(output: &ConsensusOutput) {
+    let package = &output.package;
+    println!(
+        "Proven slot range: {}..={}",
+        package.first_slot, package.last_slot
+    );
+
+    for (slot, data) in &package.slot_data {
+        match data.bank_hash {
+            Some(bank_hash) => println!(
+                "  Slot {}: bank hash {}",
+                slot,
+                bs58::encode(bank_hash).into_string()
+            ),
+            None => println!("  Slot {}: no bank hash committed", slot),
+        }
+    }
+
+    println!("Proven accounts: {}", output.account_count());
+    for (slot, proofs) in &package.proofs {
+        for (pubkey, (entry, _)) in proofs {
+            println!(
+                "  Slot {}: account {} (owner {})",
+                slot,
+                bs58::encode(pubkey).into_string(),
+                bs58::encode(entry.account.owner).into_string()
+            );
+        }
+    }
+
+    println!("Votes: {}", package.votes.len());
+}
+
+/// Verify a Solana consensus proof using SP1, recording what was checked in `report`
+fn verify_proof(
+    args: &Args,
+    allowed_vkeys: Option<VkeyAllowList>,
+    report: &mut VerificationReport,
+    text: bool,
+) -> Result<VerificationOutcome, Box<dyn Error>> {
+    // Get the proof and verification key paths
+    let proof_path = Path::new(&args.proof_path);
+    let vkey_path = Path::new(&args.vkey_path);
+
+    let expectations = ConsensusExpectations {
+        first_slot: args.expect_first_slot,
+        last_slot: args.expect_last_slot,
+        bank_hashes: args.expect_bank_hash.clone(),
+    };
+
+    let load_start = Instant::now();
+    let outcome = if args.native {
+        if text {
+            println!("Loading proof from: {}", proof_path.display());
+        }
+        let (file, bytes) = read_input(proof_path)?;
+        report.proof = Some(file);
+        let proof = NativeProof::from_json(&bytes)?;
+        report.proof_kind = Some(proof.kind().to_string());
+
+        if text {
+            println!("Loading verification key from: {}", vkey_path.display());
+        }
+        let (file, bytes) = read_input(vkey_path)?;
+        report.vkey = Some(file);
+        let vk = parse_vkey_json(&bytes)?;
+
+        let mut verifier = NativeVerifier::new(vk.bytes32()).with_expectations(expectations);
+        if let Some(allowed_vkeys) = allowed_vkeys {
+            verifier = verifier.with_allowed_vkeys(allowed_vkeys);
+        }
+        if let Some(proof_kind) = args.proof_kind.required() {
+            verifier = verifier.with_proof_kind(proof_kind);
+        }
+        if args.check_bank_hashes {
+            verifier = verifier.with_bank_hash_check(BTreeMap::new());
+        }
+        report.timings.load_ms = Timings::millis(load_start.elapsed());
+
+        if text {
+            println!("Performing native Groth16 verification...");
+        }
+        let verify_start = Instant::now();
+        let outcome = verifier.verify(&proof);
+        report.timings.verify_ms = Timings::millis(verify_start.elapsed());
+        outcome
+    } else {
+        if text {
+            println!("Loading proof from: {}", proof_path.display());
+        }
+        let (file, bytes) = read_input(proof_path)?;
+        report.proof = Some(file);
+        let proof = parse_proof(&bytes)?;
+        report.proof_kind = Some(ProofKind::of(&proof.proof).to_string());
 
-    // Create a prover client from environment
-    let client = ProverClient::from_env();
+        if text {
+            println!("Loading verification key from: {}", vkey_path.display());
+        }
+        let (file, bytes) = read_input(vkey_path)?;
+        report.vkey = Some(file);
+        let vk = parse_vkey_json(&bytes)?;
 
-    println!("Performing verification...");
+        let verifier = build_verifier(args, allowed_vkeys).with_expectations(expectations);
+        report.timings.load_ms = Timings::millis(load_start.elapsed());
 
-    // Use panic::catch_unwind to catch any panics during verification
-    let verification_result = panic::catch_unwind(AssertUnwindSafe(|| client.verify(&proof, &vk)));
+        if text {
+            println!("Performing verification...");
+        }
+        let verify_start = Instant::now();
+        let outcome = verifier.verify(&proof, &vk);
+        report.timings.verify_ms = Timings::millis(verify_start.elapsed());
+        outcome
+    };
+    report.record_outcome(&outcome);
 
-    match verification_result {
-        Ok(result) => match result {
-            Ok(_) => {
+    if text {
+        match &outcome.status {
+            VerificationStatus::Valid => {
                 println!("✅ VERIFICATION SUCCESSFUL: The Solana consensus proof is valid!");
-                Ok(true)
+                if let Some(program_version) = &outcome.program_version {
+                    println!("Program version: {}", program_version);
+                }
+
+                match outcome.consensus() {
+                    Ok(output) => print_consensus_summary(&output),
+                    Err(e) => println!("Could not decode public values: {}", e),
+                }
             }
-            Err(e) => {
+            VerificationStatus::Invalid { error, details } => {
                 println!("❌ VERIFICATION FAILED: The Solana consensus proof is invalid.");
-                println!("Error: {}", e);
-                Ok(false)
+                println!("Error [{}]: {}", error.code(), error);
+
+                // For debugging purposes, print the original error with a prefix
+                println!("\nDetailed error information (for debugging):");
+                println!("{}", details);
             }
-        },
-        Err(panic_payload) => {
-            // Handle panic by extracting a user-friendly error message
-            let panic_message = match panic_payload.downcast_ref::<String>() {
-                Some(s) => s.to_string(),
-                None => match panic_payload.downcast_ref::<&str>() {
-                    Some(s) => s.to_string(),
-                    None => "Unknown panic occurred during verification".to_string(),
-                },
-            };
+        }
+    }
 
-            let user_friendly_message = extract_error_message(&panic_message);
+    Ok(outcome)
+}
 
-            println!("❌ VERIFICATION FAILED: The Solana consensus proof is invalid.");
-            println!("Error: {}", user_friendly_message);
+/// Record a verified proof in the light-client store
+fn store_outcome(
+    path: &Path,
+    outcome: &VerificationOutcome,
+    report: &mut VerificationReport,
+    text: bool,
+) -> Result<(), Box<dyn Error>> {
+    let proof_sha256 = report
+        .proof
+        .as_ref()
+        .map(|file| file.sha256.clone())
+        .unwrap_or_default();
+    let record = StoreRecord::from_outcome(outcome, proof_sha256)?;
+    let (first_slot, last_slot) = (record.first_slot, record.last_slot);
 
-            // For debugging purposes, print the original error with a prefix
-            println!("\nDetailed error information (for debugging):");
-            println!("{}", panic_message);
+    let mut store = SlotStore::open(path)?;
+    let recorded = store.record(record);
+    report.checks.push(CheckResult {
+        name: "store",
+        status: match recorded {
+            Ok(_) => CheckStatus::Passed,
+            Err(_) => CheckStatus::Failed,
+        },
+    });
 
-            Ok(false)
+    if recorded? {
+        if text {
+            println!(
+                "Recorded slots {}..={} in store: {}",
+                first_slot,
+                last_slot,
+                path.display()
+            );
         }
+    } else if text {
+        println!("Proof already recorded in store: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Error code and exit code for an error that stopped the program before a verdict
+fn classify_error(error: &(dyn Error + 'static)) -> (&'static str, u8) {
+    if let Some(error) = error.downcast_ref::<VerifyError>() {
+        (error.code(), error.exit_code())
+    } else if error.is::<io::Error>() {
+        ("io_error", 2)
+    } else if error.is::<serde_json::Error>() {
+        ("malformed_input", 2)
+    } else {
+        ("internal_error", 5)
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    println!("Twine Solana Consensus Proof Verifier");
-    println!("=====================================");
+/// Exit code for an error that stopped the program before a verdict
+fn exit_code(error: &(dyn Error + 'static)) -> ExitCode {
+    ExitCode::from(classify_error(error).1)
+}
 
+/// Record an error that stopped the program before a verdict in the report
+fn report_error(report: &mut VerificationReport, error: &(dyn Error + 'static)) {
+    report.valid = false;
+    report.error = Some(ReportError {
+        code: classify_error(error).0,
+        message: error.to_string(),
+    });
+}
+
+fn main() -> ExitCode {
     // Parse command line arguments
     let args = Args::parse();
 
-    // Get the proof and verification key paths
-    let proof_path = Path::new(&args.proof_path);
-    let vkey_path = Path::new(&args.vkey_path);
+    // A proof converted to stdout must not be preceded by the banner
+    let converts_to_stdout = matches!(
+        &args.command,
+        Some(Command::Convert { output, .. }) if output == "-"
+    );
+    if args.output == OutputFormat::Text && !converts_to_stdout {
+        println!("Twine Solana Consensus Proof Verifier");
+        println!("=====================================");
+    }
+
+    let result = match &args.command {
+        Some(Command::Inspect { proof_path }) => inspect_proof(Path::new(proof_path)),
+        Some(Command::Convert { input, output, to }) => {
+            convert_proof(Path::new(input), Path::new(output), to.map(Into::into))
+        }
+        Some(Command::Vkey {
+            vkey_path,
+            export_groth16,
+        }) => show_vkey(
+            Path::new(vkey_path),
+            export_groth16.as_deref().map(Path::new),
+        ),
+        Some(Command::VerifyBatch {
+            proofs,
+            vkey_path,
+            manifest,
+        }) => {
+            return run_batch(
+                &args,
+                proofs.as_deref(),
+                Path::new(vkey_path),
+                manifest.as_deref().map(Path::new),
+            )
+        }
+        Some(Command::VerifyChain {
+            vkey_path,
+            allow_gap,
+            proofs,
+        }) => return run_chain(&args, proofs, Path::new(vkey_path), allow_gap),
+        Some(Command::CheckPackage { package }) => {
+            return run_check_package(&args, Path::new(package))
+        }
+        Some(Command::MatchPackage {
+            proof_path,
+            package,
+        }) => return run_match_package(&args, Path::new(proof_path), Path::new(package)),
+        Some(Command::Deposits { program_id }) => {
+            return run_deposits(&args, &DepositDecoder::new(*program_id))
+        }
+        Some(Command::Serve {
+            bind,
+            vkey_path,
+            vkey,
+            threads,
+            max_decompressed_bytes,
+        }) => serve(
+            &args,
+            bind,
+            Path::new(vkey_path),
+            vkey,
+            *threads,
+            *max_decompressed_bytes,
+        ),
+        None => return run_verification(&args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit_code(e.as_ref())
+        }
+    }
+}
+
+/// Serve verification over HTTP until the process is stopped
+fn serve(
+    args: &Args,
+    bind: &str,
+    vkey_path: &Path,
+    vkeys: &[(String, String)],
+    threads: usize,
+    max_decompressed_bytes: u64,
+) -> Result<(), Box<dyn Error>> {
+    let verifier = build_verifier(args, load_allowed_vkeys(args)?);
+
+    println!("Loading verification key from: {}", vkey_path.display());
+    let mut server = VerifyServer::new(verifier)
+        .with_max_decompressed_bytes(max_decompressed_bytes)
+        .with_vkey("default", load_vkey(vkey_path)?);
+    for (id, path) in vkeys {
+        println!("Loading verification key '{}' from: {}", id, path);
+        server = server.with_vkey(id.clone(), load_vkey(Path::new(path))?);
+    }
+    if let Some(path) = &args.store {
+        server = server.with_store(SlotStore::open(Path::new(path))?);
+    }
+
+    println!("Listening on http://{}", bind);
+    server.serve(bind, threads)
+}
+
+/// Create a prover client verifier with the checks configured on the command line
+fn build_verifier(args: &Args, allowed_vkeys: Option<VkeyAllowList>) -> Verifier {
+    let mut verifier = Verifier::from_env();
+    if let Some(allowed_vkeys) = allowed_vkeys {
+        verifier = verifier.with_allowed_vkeys(allowed_vkeys);
+    }
+    if let Some(proof_kind) = args.proof_kind.required() {
+        verifier = verifier.with_proof_kind(proof_kind);
+    }
+    if args.check_bank_hashes {
+        verifier = verifier.with_bank_hash_check(BTreeMap::new());
+    }
+    verifier
+}
+
+/// Load the allow-list given on the command line, if any.
+///
+/// An allow-list is only enforced when one was given.
+fn load_allowed_vkeys(args: &Args) -> Result<Option<VkeyAllowList>, Box<dyn Error>> {
+    if args.allowed_vkeys.is_none() && args.allowed_vkey.is_empty() {
+        return Ok(None);
+    }
+
+    let mut allowed = match &args.allowed_vkeys {
+        Some(path) => VkeyAllowList::load(Path::new(path))?,
+        None => VkeyAllowList::default(),
+    };
+    allowed.vkeys.extend(args.allowed_vkey.iter().cloned());
+    Ok(Some(allowed))
+}
+
+/// Verify the proof given on the command line, exiting with a code per failure class
+fn run_verification(args: &Args) -> ExitCode {
+    let start = Instant::now();
+    let text = args.output == OutputFormat::Text;
+    let mut report = VerificationReport::default();
+
+    let allowed_vkeys = match load_allowed_vkeys(args) {
+        Ok(allowed_vkeys) => allowed_vkeys,
+        Err(e) => {
+            if text {
+                println!("Error loading allow-list: {}", e);
+            } else {
+                report_error(&mut report, e.as_ref());
+                print_report(&mut report, start);
+            }
+            return exit_code(e.as_ref());
+        }
+    };
+
+    // Verify the proof, then record it in the store if one was given
+    let result = verify_proof(args, allowed_vkeys, &mut report, text).and_then(|outcome| {
+        if let (VerificationStatus::Valid, Some(path)) = (&outcome.status, &args.store) {
+            store_outcome(Path::new(path), &outcome, &mut report, text)?;
+        }
+        Ok(outcome.status)
+    });
 
-    // Verify the proof
-    match verify_proof(proof_path, vkey_path) {
-        Ok(true) => {
-            println!("Verification completed successfully!");
-            Ok(())
+    let code = match result {
+        Ok(VerificationStatus::Valid) => {
+            if text {
+                println!("Verification completed successfully!");
+            }
+            ExitCode::SUCCESS
         }
-        Ok(false) => {
-            println!("Verification failed!");
-            Err("Proof verification failed".into())
+        Ok(VerificationStatus::Invalid { error, .. }) => {
+            if text {
+                println!("Verification failed!");
+            }
+            ExitCode::from(error.exit_code())
         }
         Err(e) => {
-            println!("Error verifying proof: {}", e);
-            Err(e)
+            if text {
+                println!("Error verifying proof: {}", e);
+            } else {
+                report_error(&mut report, e.as_ref());
+            }
+            exit_code(e.as_ref())
         }
+    };
+
+    if !text {
+        print_report(&mut report, start);
+    }
+    code
+}
+
+/// Print a report as pretty JSON
+fn print_json(report: &impl Serialize) {
+    match serde_json::to_string_pretty(report) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Error serializing report: {}", e),
+    }
+}
+
+/// Print the JSON report of a verification started at `start`
+fn print_report(report: &mut VerificationReport, start: Instant) {
+    report.timings.total_ms = Timings::millis(start.elapsed());
+    print_json(report);
+}
+
+/// Verify a batch of proofs, exiting with the code of the first failure
+fn run_batch(
+    args: &Args,
+    proofs: Option<&str>,
+    vkey_path: &Path,
+    manifest: Option<&Path>,
+) -> ExitCode {
+    let text = args.output == OutputFormat::Text;
+    let loaded = load_allowed_vkeys(args).and_then(|allowed_vkeys| {
+        let manifest = match (manifest, proofs) {
+            (Some(path), _) => BatchManifest::load(path)?,
+            (None, Some(pattern)) => BatchManifest::from_glob(pattern, vkey_path)?,
+            (None, None) => return Err("either --proofs or --manifest is required".into()),
+        };
+        Ok((allowed_vkeys, manifest))
+    });
+    let (allowed_vkeys, manifest) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return exit_code(e.as_ref());
+        }
+    };
+
+    let verifier = build_verifier(args, allowed_vkeys);
+
+    if text {
+        println!("Verifying {} proofs...", manifest.proofs.len());
+    }
+    let start = Instant::now();
+    let results = verifier.verify_batch(&manifest);
+
+    if text {
+        print_batch_results(&results, start.elapsed().as_secs_f64());
+    } else {
+        print_json(&results);
+    }
+
+    match results.iter().find_map(|result| result.error.as_ref()) {
+        Some(error) => ExitCode::from(error.exit_code()),
+        None => ExitCode::SUCCESS,
+    }
+}
+
+/// Print one line per proof followed by the number of proofs per result
+fn print_batch_results(results: &[BatchResult], elapsed_secs: f64) {
+    println!(
+        "{:<48} {:<32} {:<24} {:>10}",
+        "Proof", "Result", "Slots", "Time (ms)"
+    );
+    for result in results {
+        let report = &result.report;
+        let outcome = match &result.error {
+            None => "valid",
+            Some(error) => error.code(),
+        };
+        let slots = match (report.first_slot, report.last_slot) {
+            (Some(first_slot), Some(last_slot)) => format!("{}..={}", first_slot, last_slot),
+            _ => "-".to_string(),
+        };
+        println!(
+            "{:<48} {:<32} {:<24} {:>10.1}",
+            result.entry.proof.display(),
+            outcome,
+            slots,
+            report.timings.total_ms
+        );
+    }
+
+    let mut counts = BTreeMap::new();
+    for result in results {
+        let outcome = result.error.as_ref().map_or("valid", |error| error.code());
+        *counts.entry(outcome).or_insert(0usize) += 1;
+    }
+
+    println!("\nSummary:");
+    for (outcome, count) in &counts {
+        println!("  {:<32} {:>6}", outcome, count);
+    }
+    println!("  {:<32} {:>6}", "total", results.len());
+    println!("Finished in {:.1}s", elapsed_secs);
+}
+
+/// Verify an ordered sequence of proofs and check that consecutive proofs link up
+fn run_chain(
+    args: &Args,
+    proofs: &[String],
+    vkey_path: &Path,
+    allowed_gaps: &[(u64, u64)],
+) -> ExitCode {
+    let text = args.output == OutputFormat::Text;
+    let manifest = BatchManifest {
+        proofs: proofs
+            .iter()
+            .map(|proof| BatchEntry {
+                proof: proof.into(),
+                vkey: vkey_path.to_path_buf(),
+            })
+            .collect(),
+    };
+
+    let verifier = match load_allowed_vkeys(args) {
+        Ok(allowed_vkeys) => build_verifier(args, allowed_vkeys),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return exit_code(e.as_ref());
+        }
+    };
+
+    if text {
+        println!("Verifying a chain of {} proofs...", manifest.proofs.len());
+    }
+    let start = Instant::now();
+    let checker = ChainChecker {
+        allowed_gaps: allowed_gaps.to_vec(),
+    };
+    let chain = verifier.verify_chain(&manifest, &checker);
+
+    if text {
+        print_batch_results(&chain.proofs, start.elapsed().as_secs_f64());
+        println!();
+        match (
+            &chain.failure,
+            chain.packages.first(),
+            chain.packages.last(),
+        ) {
+            (Some(error), _, _) => {
+                println!("❌ CHAIN NOT CHECKED: not every proof is valid ({})", error)
+            }
+            (None, Some(first), Some(last)) if chain.breaks.is_empty() => println!(
+                "✅ CHAIN VERIFIED: {} proofs cover slots {}..={}",
+                chain.packages.len(),
+                first.first_slot,
+                last.last_slot
+            ),
+            _ => {
+                println!("❌ CHAIN BROKEN:");
+                for (index, chain_break) in &chain.breaks {
+                    println!("  {}: {}", proofs[*index], chain_break);
+                }
+            }
+        }
+    } else {
+        print_json(&json!({
+            "valid": chain.is_valid(),
+            "proofs": chain.proofs,
+            "breaks": chain
+                .breaks
+                .iter()
+                .map(|(index, chain_break)| json!({
+                    "proof": proofs[*index],
+                    "error": chain_break.to_string(),
+                }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    match chain.failure {
+        Some(error) => ExitCode::from(error.exit_code()),
+        // A break in the chain is a consensus-level failure
+        None if !chain.breaks.is_empty() => ExitCode::from(4),
+        None => ExitCode::SUCCESS,
+    }
+}
+
+/// Run the consensus constraint checks against a witness package
+fn run_check_package(args: &Args, path: &Path) -> ExitCode {
+    let text = args.output == OutputFormat::Text;
+    if text {
+        println!("Loading package from: {}", path.display());
+    }
+    let package = match load_package(path) {
+        Ok(package) => package,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return exit_code(e.as_ref());
+        }
+    };
+
+    let checks = check_package(&package);
+    let valid = checks.iter().all(|check| check.passed());
+
+    if text {
+        println!(
+            "Checking slots {}..={}, {} accounts, {} votes",
+            package.first_slot,
+            package.last_slot,
+            package.proofs.values().map(Vec::len).sum::<usize>(),
+            package.votes.len()
+        );
+        for check in &checks {
+            let mark = if check.passed() { "✅" } else { "❌" };
+            println!("{} {}", mark, check.name);
+            for issue in &check.issues {
+                println!("    {}", issue);
+            }
+        }
+        if valid {
+            println!("✅ PACKAGE VALID: every constraint holds");
+        } else {
+            println!("❌ PACKAGE INVALID");
+        }
+    } else {
+        let report = json!({
+            "valid": valid,
+            "first_slot": package.first_slot,
+            "last_slot": package.last_slot,
+            "checks": checks
+                .iter()
+                .map(|check| json!({
+                    "name": check.name,
+                    "status": if check.passed() { "passed" } else { "failed" },
+                    "issues": check.issues.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        print_json(&report);
+    }
+
+    if valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(4)
+    }
+}
+
+/// Load a proof's decoded public values and a witness package
+fn load_match_inputs(
+    proof_path: &Path,
+    package_path: &Path,
+    text: bool,
+) -> Result<(InputFile, ConsensusOutput, ConsensusPackage), Box<dyn Error>> {
+    if text {
+        println!("Loading proof from: {}", proof_path.display());
+    }
+    let (file, bytes) = read_input(proof_path)?;
+    let proof = parse_proof(&bytes)?;
+    let output = decode_public_values(proof.public_values.as_slice())
+        .map_err(|e| VerifyError::MalformedPublicValues(e.to_string()))?;
+
+    if text {
+        println!("Loading package from: {}", package_path.display());
+    }
+    Ok((file, output, load_package(package_path)?))
+}
+
+/// Decode the public values of a proof and compare them with a witness package
+fn run_match_package(args: &Args, proof_path: &Path, package_path: &Path) -> ExitCode {
+    let text = args.output == OutputFormat::Text;
+    let loaded = load_match_inputs(proof_path, package_path, text);
+    let (file, output, package) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return exit_code(e.as_ref());
+        }
+    };
+
+    let differences = diff_packages(&output.package, &package);
+    if text {
+        println!("Note: the proof itself is not verified by this command");
+        if differences.is_empty() {
+            println!(
+                "✅ PACKAGE MATCHES: the proof commits exactly this package (slots {}..={})",
+                package.first_slot, package.last_slot
+            );
+        } else {
+            println!("❌ PACKAGE DIFFERS in {} fields:", differences.len());
+            for difference in &differences {
+                println!("  {}", difference);
+            }
+        }
+    } else {
+        let report = json!({
+            "matches": differences.is_empty(),
+            "proof": file,
+            "differences": differences
+                .iter()
+                .map(|difference| json!({
+                    "field": difference.field,
+                    "proof": difference.committed,
+                    "package": difference.package,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        print_json(&report);
+    }
+
+    if differences.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(4)
+    }
+}
+
+/// Verify the proof, then list the deposits held by its proven accounts
+fn run_deposits(args: &Args, decoder: &DepositDecoder) -> ExitCode {
+    let text = args.output == OutputFormat::Text;
+    let mut report = VerificationReport::default();
+
+    let outcome = match load_allowed_vkeys(args)
+        .and_then(|allowed_vkeys| verify_proof(args, allowed_vkeys, &mut report, text))
+    {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            if !text {
+                report_error(&mut report, e.as_ref());
+                print_json(&json!({ "report": report }));
+            }
+            return exit_code(e.as_ref());
+        }
+    };
+    if let Some(error) = outcome.error() {
+        eprintln!("Error: not listing deposits of an invalid proof: {}", error);
+        if !text {
+            print_json(&json!({ "report": report }));
+        }
+        return ExitCode::from(error.exit_code());
+    }
+    let output = match outcome.consensus() {
+        Ok(output) => output,
+        Err(e) => {
+            let error = VerifyError::MalformedPublicValues(e.to_string());
+            eprintln!("Error: {}", error);
+            if !text {
+                report_error(&mut report, &error);
+                print_json(&json!({ "report": report }));
+            }
+            return ExitCode::from(error.exit_code());
+        }
+    };
+
+    let scan = decoder.scan(&output.package);
+    let b58 = |bytes: &[u8]| bs58::encode(bytes).into_string();
+    if text {
+        println!();
+        println!("Deposits: {}", scan.deposits.len());
+        for (slot, account, deposit) in &scan.deposits {
+            println!(
+                "  Slot {} account {}: nonce {}, {} of token {} from {} to 0x{}",
+                slot,
+                b58(account),
+                deposit.nonce,
+                deposit.amount,
+                b58(&deposit.token),
+                b58(&deposit.sender),
+                hex::encode(deposit.recipient)
+            );
+        }
+        for (slot, account, reason) in &scan.skipped {
+            println!(
+                "  Slot {} account {} skipped: {}",
+                slot,
+                b58(account),
+                reason
+            );
+        }
+    } else {
+        let deposits: Vec<_> = scan
+            .deposits
+            .iter()
+            .map(|(slot, account, deposit)| {
+                json!({
+                    "slot": slot,
+                    "account": b58(account),
+                    "sender": b58(&deposit.sender),
+                    "recipient": format!("0x{}", hex::encode(deposit.recipient)),
+                    "token": b58(&deposit.token),
+                    "amount": deposit.amount,
+                    "nonce": deposit.nonce,
+                })
+            })
+            .collect();
+        let skipped: Vec<_> = scan
+            .skipped
+            .iter()
+            .map(|(slot, account, reason)| {
+                json!({
+                    "slot": slot,
+                    "account": b58(account),
+                    "reason": reason.to_string(),
+                })
+            })
+            .collect();
+        let json = json!({ "report": report, "deposits": deposits, "skipped": skipped });
+        print_json(&json);
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_required_proof_kind() {
+        let args = Args::try_parse_from(["verifier", "--proof-kind", "plonk"]).unwrap();
+        assert_eq!(args.proof_kind.required(), Some(ProofKind::Plonk));
+
+        let args = Args::try_parse_from(["verifier"]).unwrap();
+        assert_eq!(args.proof_kind.required(), None);
+
+        assert!(Args::try_parse_from(["verifier", "--proof-kind", "stark"]).is_err());
+    }
+    #[test]
+    fn requires_deposit_program_id() {
+        assert!(Args::try_parse_from(["verifier", "deposits"]).is_err());
+
+        let args = Args::try_parse_from([
+            "verifier",
+            "deposits",
+            "--program-id",
+            "11111111111111111111111111111111",
+        ])
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Deposits { program_id }) if program_id == [0; 32]
+        ));
     }
 }
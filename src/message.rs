@@ -0,0 +1,52 @@
+//! Legacy Solana transaction messages as carried by vote records.
+//!
+//! Field names follow Solana's JSON representation (`accountKeys`,
+//! `recentBlockhash`, ...) and sequences use the compact-u16 encoding, so
+//! bincode serialization of a [`Message`] yields the exact wire bytes that
+//! the validator signed.
+
+use crate::public_values::{Hash, Pubkey};
+use crate::short_vec;
+use serde::{Deserialize, Serialize};
+
+/// Counts of signed and read-only accounts in a message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageHeader {
+    /// Number of signatures required, covering the first account keys
+    pub num_required_signatures: u8,
+    /// Number of signed accounts that are read-only
+    pub num_readonly_signed_accounts: u8,
+    /// Number of unsigned accounts that are read-only
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+/// An instruction referencing accounts by index into the message keys
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompiledInstruction {
+    /// Index of the program account in `account_keys`
+    pub program_id_index: u8,
+    /// Indices of the instruction accounts in `account_keys`
+    #[serde(with = "short_vec")]
+    pub accounts: Vec<u8>,
+    /// Program specific instruction data
+    #[serde(with = "short_vec")]
+    pub data: Vec<u8>,
+}
+
+/// A legacy (pre-versioned) Solana transaction message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    /// Signature and read-only account counts
+    pub header: MessageHeader,
+    /// All accounts referenced by the message, signers first
+    #[serde(with = "short_vec")]
+    pub account_keys: Vec<Pubkey>,
+    /// Blockhash the transaction was built against
+    pub recent_blockhash: Hash,
+    /// Instructions executed by the transaction
+    #[serde(with = "short_vec")]
+    pub instructions: Vec<CompiledInstruction>,
+}
@@ -0,0 +1,376 @@
+//! Groth16 verification without the SP1 prover client.
+//!
+//! This path reads the proof JSON with plain serde types and checks the
+//! Groth16 proof against the SP1 Groth16 verifying key embedded in
+//! `sp1-verifier` using BN254 pairing arithmetic. It does not depend on
+//! `sp1-sdk`, so it builds without the `prover` feature and for wasm.
+
+use crate::allowlist::{normalize_vkey_hash, VkeyAllowList};
+use crate::bank_hash::BankHashExtras;
+use crate::compat::expected_circuit_version;
+use crate::digest::check_committed_values_digest;
+use crate::error::VerifyError;
+use crate::expect::ConsensusExpectations;
+use crate::groth16_vk::check_proof_points;
+use crate::outcome::{Checks, VerificationOutcome};
+use crate::proof_kind::ProofKind;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sp1_verifier::{Groth16Verifier, GROTH16_VK_BYTES};
+use std::collections::BTreeMap;
+
+/// A Groth16 proof as serialized in `SP1ProofWithPublicValues` JSON
+#[derive(Debug, Clone, Deserialize)]
+pub struct Groth16ProofJson {
+    /// Program vkey hash and committed values digest, as decimal field elements
+    pub public_inputs: [String; 2],
+    /// Hex encoded gnark proof
+    pub encoded_proof: String,
+    /// Hex encoded raw proof points
+    pub raw_proof: String,
+    /// SHA-256 of the Groth16 circuit verifying key the proof was made for
+    pub groth16_vkey_hash: [u8; 32],
+}
+
+/// The proof variants of `SP1ProofWithPublicValues` JSON; only Groth16 is decoded
+#[derive(Debug, Clone, Deserialize)]
+pub enum ProofJson {
+    Core(IgnoredAny),
+    Compressed(IgnoredAny),
+    Plonk(IgnoredAny),
+    Groth16(Groth16ProofJson),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BufferJson {
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PublicValuesJson {
+    buffer: BufferJson,
+}
+
+/// `SP1ProofWithPublicValues` JSON read without `sp1-sdk`
+#[derive(Debug, Clone, Deserialize)]
+pub struct NativeProof {
+    /// The proof
+    pub proof: ProofJson,
+    #[serde(rename = "public_values")]
+    public_values_json: PublicValuesJson,
+    /// SP1 version recorded in the proof
+    pub sp1_version: String,
+}
+
+impl NativeProof {
+    /// Parse a proof from its JSON representation
+    pub fn from_json(bytes: &[u8]) -> Result<Self, VerifyError> {
+        serde_json::from_slice(bytes).map_err(|e| VerifyError::MalformedProofJson(e.to_string()))
+    }
+
+    /// Raw public values committed by the program
+    pub fn public_values(&self) -> &[u8] {
+        &self.public_values_json.buffer.data
+    }
+
+    /// The proof variant
+    pub fn kind(&self) -> ProofKind {
+        match &self.proof {
+            ProofJson::Core(_) => ProofKind::Core,
+            ProofJson::Compressed(_) => ProofKind::Compressed,
+            ProofJson::Plonk(_) => ProofKind::Plonk,
+            ProofJson::Groth16(_) => ProofKind::Groth16,
+        }
+    }
+}
+
+/// SHA-256 of the SP1 Groth16 circuit verifying key embedded in this build
+pub fn embedded_groth16_vkey_hash() -> [u8; 32] {
+    Sha256::digest(&GROTH16_VK_BYTES[..]).into()
+}
+
+/// Parse a decimal BN254 field element into 32 big-endian bytes
+pub fn decimal_to_bytes32(s: &str) -> Option<[u8; 32]> {
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut bytes = [0u8; 32];
+    for c in s.chars() {
+        let mut carry = c.to_digit(10)?;
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(bytes)
+}
+
+/// Format 32 big-endian bytes as a decimal number
+pub fn bytes32_to_decimal(bytes: &[u8; 32]) -> String {
+    let mut value = *bytes;
+    let mut digits = Vec::new();
+
+    while value.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for byte in value.iter_mut() {
+            let acc = (remainder << 8) | u32::from(*byte);
+            *byte = (acc / 10) as u8;
+            remainder = acc % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("decimal digits are ASCII")
+}
+
+/// Verifies Groth16 consensus proofs with pure pairing arithmetic
+pub struct NativeVerifier {
+    program_vkey_hash: String,
+    expectations: ConsensusExpectations,
+    allowed_vkeys: Option<VkeyAllowList>,
+    proof_kind: Option<ProofKind>,
+    bank_hash_extras: Option<BTreeMap<u64, BankHashExtras>>,
+}
+
+impl NativeVerifier {
+    /// Create a verifier for the program with the given vkey hash (`bytes32()` form)
+    pub fn new(program_vkey_hash: impl Into<String>) -> Self {
+        Self {
+            program_vkey_hash: normalize_vkey_hash(&program_vkey_hash.into()),
+            expectations: ConsensusExpectations::default(),
+            allowed_vkeys: None,
+            proof_kind: None,
+            bank_hash_extras: None,
+        }
+    }
+
+    /// Only accept proofs of this variant
+    pub fn with_proof_kind(mut self, proof_kind: ProofKind) -> Self {
+        self.proof_kind = Some(proof_kind);
+        self
+    }
+
+    /// Only accept proofs for program vkeys in the allow-list
+    pub fn with_allowed_vkeys(mut self, allowed_vkeys: VkeyAllowList) -> Self {
+        self.allowed_vkeys = Some(allowed_vkeys);
+        self
+    }
+
+    /// Recompute every committed bank hash from its components, mixing in the
+    /// extras given for a slot. Slots without extras are assumed to need none.
+    pub fn with_bank_hash_check(mut self, extras: BTreeMap<u64, BankHashExtras>) -> Self {
+        self.bank_hash_extras = Some(extras);
+        self
+    }
+
+    /// Require verified proofs to commit to the expected consensus data
+    pub fn with_expectations(mut self, expectations: ConsensusExpectations) -> Self {
+        self.expectations = expectations;
+        self
+    }
+
+    /// Verify a Groth16 proof against the embedded SP1 Groth16 verifying key.
+    ///
+    /// Other proof variants fail the `proof_kind` check.
+    pub fn verify(&self, proof: &NativeProof) -> VerificationOutcome {
+        // Every check after `proof_kind` only runs if the proof is Groth16
+        let groth16 = || match &proof.proof {
+            ProofJson::Groth16(groth16) => Ok(groth16),
+            _ => Err(VerifyError::UnsupportedProofKind(proof.kind().to_string())),
+        };
+
+        let program_version = self
+            .allowed_vkeys
+            .as_ref()
+            .and_then(|allowed| allowed.lookup(&self.program_vkey_hash))
+            .map(|vkey| vkey.label.clone());
+
+        let public_values = proof.public_values();
+        let mut checks = Checks::default();
+        checks.run("proof_kind", || {
+            ProofKind::check(self.proof_kind, proof.kind())?;
+            groth16().map(|_| ())
+        });
+        match &self.allowed_vkeys {
+            Some(allowed) => checks.run("vkey_allowed", || {
+                allowed.check(&self.program_vkey_hash).map(|_| ())
+            }),
+            None => checks.skip("vkey_allowed"),
+        }
+        checks.run("circuit_version", || {
+            check_circuit_version(groth16()?, proof)
+        });
+        checks.run("program_vkey_hash", || {
+            self.check_program_vkey_hash(groth16()?)
+        });
+        checks.run("public_values_digest", || {
+            check_committed_values_digest(&groth16()?.public_inputs[1], public_values)
+        });
+        checks.run("curve_points", || {
+            check_proof_points(&decode_encoded_proof(groth16()?)?)
+        });
+        checks.run("pairing", || self.check_pairing(groth16()?, public_values));
+        checks.run_consensus(
+            public_values,
+            self.bank_hash_extras.as_ref(),
+            &self.expectations,
+        );
+        let (status, checks) = checks.finish();
+
+        VerificationOutcome {
+            status,
+            vkey_hash: self.program_vkey_hash.clone(),
+            program_version,
+            sp1_version: proof.sp1_version.clone(),
+            public_values: public_values.to_vec(),
+            checks,
+        }
+    }
+
+    /// Verify a proof given as JSON bytes
+    pub fn verify_json(&self, proof_json: &[u8]) -> Result<VerificationOutcome, VerifyError> {
+        Ok(self.verify(&NativeProof::from_json(proof_json)?))
+    }
+
+    fn check_program_vkey_hash(&self, groth16: &Groth16ProofJson) -> Result<(), VerifyError> {
+        let committed_vkey_hash = decimal_to_bytes32(&groth16.public_inputs[0])
+            .map(|hash| format!("0x{}", hex::encode(hash)));
+        if committed_vkey_hash.as_deref() != Some(self.program_vkey_hash.as_str()) {
+            return Err(VerifyError::VkeyHashMismatch {
+                expected: self.program_vkey_hash.clone(),
+                actual: committed_vkey_hash.unwrap_or_else(|| groth16.public_inputs[0].clone()),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_pairing(
+        &self,
+        groth16: &Groth16ProofJson,
+        public_values: &[u8],
+    ) -> Result<(), VerifyError> {
+        // `sp1-verifier` expects the proof prefixed with the circuit vkey hash selector
+        // and the program vkey hash in its `0x` prefixed form
+        let proof_bytes = [
+            &groth16.groth16_vkey_hash[..4],
+            &decode_encoded_proof(groth16)?[..],
+        ]
+        .concat();
+
+        Groth16Verifier::verify(
+            &proof_bytes,
+            public_values,
+            &self.program_vkey_hash,
+            &GROTH16_VK_BYTES,
+        )
+        .map_err(|e| VerifyError::PairingCheckFailed(e.to_string()))
+    }
+}
+
+/// Check that the proof was made for the Groth16 circuit embedded in this build
+fn check_circuit_version(
+    groth16: &Groth16ProofJson,
+    proof: &NativeProof,
+) -> Result<(), VerifyError> {
+    let embedded_vkey_hash = embedded_groth16_vkey_hash();
+    if groth16.groth16_vkey_hash != embedded_vkey_hash {
+        return Err(VerifyError::VersionMismatch {
+            proof: format!(
+                "{} (circuit {})",
+                proof.sp1_version,
+                hex::encode(groth16.groth16_vkey_hash)
+            ),
+            expected: format!(
+                "{} (circuit {})",
+                expected_circuit_version(),
+                hex::encode(embedded_vkey_hash)
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn decode_encoded_proof(groth16: &Groth16ProofJson) -> Result<Vec<u8>, VerifyError> {
+    hex::decode(&groth16.encoded_proof)
+        .map_err(|e| VerifyError::MalformedProofJson(format!("invalid encoded_proof hex: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outcome::CheckStatus;
+    use std::fs;
+
+    const SAMPLE_VKEY_HASH: &str =
+        "0x00cb04968f45082eb851c86ccbf309b0407afcc28121a81f87438a95ddc9c18c";
+
+    fn proof_json(variant: &str) -> String {
+        format!(
+            r#"{{"proof": {{"{}": {{"any": [1, 2, 3]}}}}, "public_values": {{"buffer": {{"data": [7, 8]}}}}, "sp1_version": "v4.0.0-rc.3"}}"#,
+            variant
+        )
+    }
+
+    fn sample_proof() -> NativeProof {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/groth16_proof.json");
+        NativeProof::from_json(&fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_every_proof_variant() {
+        for (variant, kind) in [
+            ("Core", ProofKind::Core),
+            ("Compressed", ProofKind::Compressed),
+            ("Plonk", ProofKind::Plonk),
+        ] {
+            let proof = NativeProof::from_json(proof_json(variant).as_bytes()).unwrap();
+            assert_eq!(proof.kind(), kind);
+            assert_eq!(proof.public_values(), [7, 8]);
+        }
+        assert_eq!(sample_proof().kind(), ProofKind::Groth16);
+    }
+
+    #[test]
+    fn rejects_unknown_proof_variant() {
+        let error = NativeProof::from_json(proof_json("Stark").as_bytes()).unwrap_err();
+        assert_eq!(error.code(), "malformed_proof_json");
+    }
+
+    #[test]
+    fn fails_proof_kind_check_for_other_variants() {
+        let proof = NativeProof::from_json(proof_json("Plonk").as_bytes()).unwrap();
+        let outcome = NativeVerifier::new(SAMPLE_VKEY_HASH).verify(&proof);
+
+        assert_eq!(
+            outcome.error(),
+            Some(&VerifyError::UnsupportedProofKind("Plonk".to_string()))
+        );
+        assert_eq!(outcome.checks[0].name, "proof_kind");
+        assert_eq!(outcome.checks[0].status, CheckStatus::Failed);
+        assert!(outcome.checks[1..]
+            .iter()
+            .all(|check| check.status == CheckStatus::Skipped));
+    }
+
+    #[test]
+    fn rejects_proof_of_unexpected_kind() {
+        let outcome = NativeVerifier::new(SAMPLE_VKEY_HASH)
+            .with_proof_kind(ProofKind::Plonk)
+            .verify(&sample_proof());
+
+        let error = outcome.error().unwrap();
+        assert_eq!(error.code(), "unexpected_proof_kind");
+        assert_eq!(error.exit_code(), 3);
+        assert_eq!(outcome.checks[0].status, CheckStatus::Failed);
+    }
+}
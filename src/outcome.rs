@@ -0,0 +1,174 @@
+use crate::account_delta::verify_account_proofs;
+use crate::bank_hash::{check_package_bank_hashes, BankHashExtras};
+use crate::error::VerifyError;
+use crate::expect::ConsensusExpectations;
+use crate::public_values::{decode_public_values, ConsensusOutput};
+use crate::vote::check_votes;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Whether a proof was accepted, and why not if it was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationStatus {
+    /// The proof is valid for the verification key
+    Valid,
+    /// The proof was rejected
+    Invalid {
+        /// Why the proof was rejected
+        error: VerifyError,
+        /// Original error or panic message, for debugging
+        details: String,
+    },
+}
+
+impl From<VerifyError> for VerificationStatus {
+    fn from(error: VerifyError) -> Self {
+        let details = format!("{:?}", error);
+        VerificationStatus::Invalid { error, details }
+    }
+}
+
+/// How a single verification check went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Passed,
+    Failed,
+    /// Not configured, or not run because an earlier check failed
+    Skipped,
+}
+
+/// A named verification check and how it went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    /// Stable check name, such as `public_values_digest`
+    pub name: &'static str,
+    pub status: CheckStatus,
+}
+
+/// Runs verification checks in order, skipping the rest after the first failure
+#[derive(Default)]
+pub(crate) struct Checks {
+    results: Vec<CheckResult>,
+    error: Option<VerifyError>,
+}
+
+impl Checks {
+    /// Run a check unless an earlier one failed
+    pub(crate) fn run(
+        &mut self,
+        name: &'static str,
+        check: impl FnOnce() -> Result<(), VerifyError>,
+    ) {
+        let status = if self.error.is_some() {
+            CheckStatus::Skipped
+        } else {
+            match check() {
+                Ok(()) => CheckStatus::Passed,
+                Err(error) => {
+                    self.error = Some(error);
+                    CheckStatus::Failed
+                }
+            }
+        };
+        self.results.push(CheckResult { name, status });
+    }
+
+    /// Record a check that is not configured
+    pub(crate) fn skip(&mut self, name: &'static str) {
+        self.results.push(CheckResult {
+            name,
+            status: CheckStatus::Skipped,
+        });
+    }
+
+    /// Decode the public values once and check the consensus data they commit to.
+    ///
+    /// Bank hashes are only recomputed when extras are given, since slots
+    /// that need extras cannot be checked without them.
+    pub(crate) fn run_consensus(
+        &mut self,
+        public_values: &[u8],
+        bank_hash_extras: Option<&BTreeMap<u64, BankHashExtras>>,
+        expectations: &ConsensusExpectations,
+    ) {
+        let output = decode_public_values(public_values)
+            .map_err(|e| VerifyError::MalformedPublicValues(e.to_string()));
+        let output = || output.as_ref().map_err(Clone::clone);
+
+        self.run("committed_result", || output()?.check_valid());
+        match bank_hash_extras {
+            Some(extras) => self.run("bank_hashes", || {
+                check_package_bank_hashes(&output()?.package, extras)
+            }),
+            None => self.skip("bank_hashes"),
+        }
+        self.run("account_proofs", || {
+            match verify_account_proofs(&output()?.package).into_iter().next() {
+                Some((slot, error)) => Err(VerifyError::InvalidAccountProof { slot, error }),
+                None => Ok(()),
+            }
+        });
+        self.run("votes", || {
+            match check_votes(&output()?.package).into_iter().next() {
+                Some((voter, error)) => Err(VerifyError::InvalidVote {
+                    voter: bs58::encode(voter).into_string(),
+                    error,
+                }),
+                None => Ok(()),
+            }
+        });
+        if expectations.is_empty() {
+            self.skip("consensus");
+        } else {
+            self.run("consensus", || Ok(expectations.check(output()?)?));
+        }
+    }
+
+    /// The overall status and the result of every check
+    pub(crate) fn finish(self) -> (VerificationStatus, Vec<CheckResult>) {
+        let status = match self.error {
+            None => VerificationStatus::Valid,
+            Some(error) => error.into(),
+        };
+        (status, self.results)
+    }
+}
+
+/// Result of verifying a single proof
+#[derive(Debug, Clone)]
+pub struct VerificationOutcome {
+    /// Whether the proof was accepted
+    pub status: VerificationStatus,
+    /// Hash of the program verification key (`bytes32()` form)
+    pub vkey_hash: String,
+    /// Allow-list label of the program version that produced the proof
+    pub program_version: Option<String>,
+    /// SP1 version recorded in the proof
+    pub sp1_version: String,
+    /// Raw public values committed by the program
+    pub public_values: Vec<u8>,
+    /// Every check in the order it was run
+    pub checks: Vec<CheckResult>,
+}
+
+impl VerificationOutcome {
+    /// Returns true if the proof was accepted
+    pub fn is_valid(&self) -> bool {
+        self.status == VerificationStatus::Valid
+    }
+
+    /// Why the proof was rejected, if it was
+    pub fn error(&self) -> Option<&VerifyError> {
+        match &self.status {
+            VerificationStatus::Valid => None,
+            VerificationStatus::Invalid { error, .. } => Some(error),
+        }
+    }
+
+    /// Decode the committed public values into the proven consensus data
+    pub fn consensus(&self) -> Result<ConsensusOutput, Box<dyn Error>> {
+        decode_public_values(&self.public_values)
+    }
+}
@@ -0,0 +1,710 @@
+//! Native re-check of a consensus witness package.
+//!
+//! Runs the constraints the zkVM program enforces (see "Verified
+//! Constraints" in the README) directly against a `package_solana.json`
+//! witness, so prover-side failures can be reproduced without generating a
+//! proof. Unlike proof verification, every check runs and reports all of its
+//! issues.
+//!
+//! [`diff_packages`] compares the package committed in a proof's public
+//! values with a witness package, to tell which collector fetch a proof was
+//! generated from.
+
+use crate::account_delta::{verify_account_proofs, AccountProofError};
+use crate::bank_hash::{compute_bank_hash, BankHashExtras};
+use crate::public_values::{
+    AccountDeltaEntry, ConsensusPackage, Hash, MerkleProof, PackageStatus, Pubkey, SlotData, Vote,
+};
+use crate::vote::{check_votes, VoteError};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A constraint violated by a package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageIssue {
+    /// `first_slot` is after `last_slot`
+    EmptyRange { first_slot: u64, last_slot: u64 },
+    /// The collector had not finished fetching the package
+    Incomplete { status: PackageStatus },
+    /// Slots of the window have no slot data
+    MissingSlotData { first_slot: u64, last_slot: u64 },
+    /// Slot data or account proofs are present for a slot outside the window
+    SlotOutOfRange { slot: u64 },
+    /// A slot whose bank hash the next slot builds on has none
+    MissingBankHash { slot: u64 },
+    /// The parent bank hash of a slot is not the bank hash of the slot before it
+    ParentBankHash {
+        slot: u64,
+        parent_slot: u64,
+        expected: Hash,
+        actual: Hash,
+    },
+    /// A bank hash does not match the slot's bank hash components
+    BankHashMismatch {
+        slot: u64,
+        committed: Hash,
+        computed: Hash,
+    },
+    /// An account delta proof does not hold
+    AccountProof { slot: u64, error: AccountProofError },
+    /// A vote has a bad signature or does not match its instruction
+    Vote { voter: Pubkey, error: VoteError },
+    /// No proven account carries any data
+    NoAccountData,
+}
+
+impl fmt::Display for PackageIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageIssue::EmptyRange {
+                first_slot,
+                last_slot,
+            } => write!(
+                f,
+                "first slot {} is after last slot {}",
+                first_slot, last_slot
+            ),
+            PackageIssue::Incomplete { status } => {
+                write!(f, "package status is {:?}, not Complete", status)
+            }
+            PackageIssue::MissingSlotData {
+                first_slot,
+                last_slot,
+            } if first_slot == last_slot => write!(f, "no slot data for slot {}", first_slot),
+            PackageIssue::MissingSlotData {
+                first_slot,
+                last_slot,
+            } => write!(f, "no slot data for slots {}..={}", first_slot, last_slot),
+            PackageIssue::SlotOutOfRange { slot } => {
+                write!(f, "slot {} is outside the package window", slot)
+            }
+            PackageIssue::MissingBankHash { slot } => {
+                write!(f, "no bank hash for slot {}", slot)
+            }
+            PackageIssue::ParentBankHash {
+                slot,
+                parent_slot,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "parent bank hash of slot {} is {}, but slot {} has bank hash {}",
+                slot,
+                bs58::encode(actual).into_string(),
+                parent_slot,
+                bs58::encode(expected).into_string()
+            ),
+            PackageIssue::BankHashMismatch {
+                slot,
+                committed,
+                computed,
+            } => write!(
+                f,
+                "slot {} has bank hash {}, but its components hash to {}",
+                slot,
+                bs58::encode(committed).into_string(),
+                bs58::encode(computed).into_string()
+            ),
+            PackageIssue::AccountProof { slot, error } => write!(f, "slot {}: {}", slot, error),
+            PackageIssue::Vote { voter, error } => write!(
+                f,
+                "vote from validator {}: {}",
+                bs58::encode(voter).into_string(),
+                error
+            ),
+            PackageIssue::NoAccountData => f.write_str("no proven account carries any data"),
+        }
+    }
+}
+
+impl Error for PackageIssue {}
+
+/// Result of one constraint check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageCheck {
+    /// Name of the constraint, e.g. `bank_hash_chain`
+    pub name: &'static str,
+    /// Every violation found; empty if the constraint holds
+    pub issues: Vec<PackageIssue>,
+}
+
+impl PackageCheck {
+    /// Returns true if the constraint holds
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Load a witness package JSON file
+pub fn load_package(path: &Path) -> Result<ConsensusPackage, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Run every constraint check against a package
+pub fn check_package(package: &ConsensusPackage) -> Vec<PackageCheck> {
+    vec![
+        PackageCheck {
+            name: "slot_completeness",
+            issues: check_slot_completeness(package),
+        },
+        PackageCheck {
+            name: "bank_hash_chain",
+            issues: check_bank_hash_chain(package),
+        },
+        PackageCheck {
+            name: "bank_hashes",
+            issues: check_bank_hashes(package),
+        },
+        PackageCheck {
+            name: "account_delta_proofs",
+            issues: verify_account_proofs(package)
+                .into_iter()
+                .map(|(slot, error)| PackageIssue::AccountProof { slot, error })
+                .collect(),
+        },
+        PackageCheck {
+            name: "vote_signatures",
+            issues: check_votes(package)
+                .into_iter()
+                .map(|(voter, error)| PackageIssue::Vote { voter, error })
+                .collect(),
+        },
+        PackageCheck {
+            name: "account_data_present",
+            issues: check_account_data_present(package),
+        },
+    ]
+}
+
+/// Every slot of the window, and no other, has slot data
+fn check_slot_completeness(package: &ConsensusPackage) -> Vec<PackageIssue> {
+    let (first_slot, last_slot) = (package.first_slot, package.last_slot);
+    if first_slot > last_slot {
+        return vec![PackageIssue::EmptyRange {
+            first_slot,
+            last_slot,
+        }];
+    }
+
+    let mut issues = Vec::new();
+    if package.status != PackageStatus::Complete {
+        issues.push(PackageIssue::Incomplete {
+            status: package.status,
+        });
+    }
+
+    // Report the gaps between the slots that have data; `None` once past `u64::MAX`
+    let mut next = Some(first_slot);
+    for &slot in package
+        .slot_data
+        .range(first_slot..=last_slot)
+        .map(|(slot, _)| slot)
+    {
+        if let Some(missing) = next.filter(|&missing| missing < slot) {
+            issues.push(PackageIssue::MissingSlotData {
+                first_slot: missing,
+                last_slot: slot - 1,
+            });
+        }
+        next = slot.checked_add(1);
+    }
+    if let Some(missing) = next.filter(|&missing| missing <= last_slot) {
+        issues.push(PackageIssue::MissingSlotData {
+            first_slot: missing,
+            last_slot,
+        });
+    }
+
+    let window = first_slot..=last_slot;
+    let mut outside: Vec<u64> = package
+        .slot_data
+        .keys()
+        .chain(package.proofs.keys())
+        .filter(|slot| !window.contains(slot))
+        .copied()
+        .collect();
+    outside.sort_unstable();
+    outside.dedup();
+    issues.extend(
+        outside
+            .into_iter()
+            .map(|slot| PackageIssue::SlotOutOfRange { slot }),
+    );
+    issues
+}
+
+/// Each slot's parent bank hash is the bank hash of the slot before it
+fn check_bank_hash_chain(package: &ConsensusPackage) -> Vec<PackageIssue> {
+    let mut issues = Vec::new();
+    let slots: Vec<_> = package.slot_data.iter().collect();
+    for pair in slots.windows(2) {
+        let ((&parent_slot, parent), (&slot, data)) = (pair[0], pair[1]);
+        let Some(expected) = parent.bank_hash else {
+            issues.push(PackageIssue::MissingBankHash { slot: parent_slot });
+            continue;
+        };
+        if data.parent_bankhash != expected {
+            issues.push(PackageIssue::ParentBankHash {
+                slot,
+                parent_slot,
+                expected,
+                actual: data.parent_bankhash,
+            });
+        }
+    }
+    issues
+}
+
+/// Each bank hash matches its components, assuming no feature-gated extras
+fn check_bank_hashes(package: &ConsensusPackage) -> Vec<PackageIssue> {
+    package
+        .slot_data
+        .iter()
+        .filter_map(|(&slot, data)| {
+            let committed = data.bank_hash?;
+            let computed = compute_bank_hash(data, &BankHashExtras::default());
+            (committed != computed).then_some(PackageIssue::BankHashMismatch {
+                slot,
+                committed,
+                computed,
+            })
+        })
+        .collect()
+}
+
+/// At least one proven account carries data
+fn check_account_data_present(package: &ConsensusPackage) -> Vec<PackageIssue> {
+    let has_data = package
+        .proofs
+        .values()
+        .flatten()
+        .any(|(_, (entry, _))| !entry.account.data.is_empty());
+    if has_data {
+        Vec::new()
+    } else {
+        vec![PackageIssue::NoAccountData]
+    }
+}
+
+/// A field on which the committed public values and a package disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageDifference {
+    /// Path of the field, e.g. `slot_data[326251073].bank_hash`
+    pub field: String,
+    /// Value in the proof's public values, or `None` if absent there
+    pub committed: Option<String>,
+    /// Value in the package, or `None` if absent there
+    pub package: Option<String>,
+}
+
+impl fmt::Display for PackageDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "missing".into());
+        write!(
+            f,
+            "{}: proof has {}, package has {}",
+            self.field,
+            show(&self.committed),
+            show(&self.package)
+        )
+    }
+}
+
+/// Collects differences between two packages under a field path
+struct Differ {
+    differences: Vec<PackageDifference>,
+}
+
+impl Differ {
+    fn value<T: PartialEq>(
+        &mut self,
+        field: String,
+        committed: &T,
+        package: &T,
+        show: impl Fn(&T) -> String,
+    ) {
+        if committed != package {
+            self.differences.push(PackageDifference {
+                field,
+                committed: Some(show(committed)),
+                package: Some(show(package)),
+            });
+        }
+    }
+
+    fn presence(&mut self, field: String, committed: Option<String>, package: Option<String>) {
+        self.differences.push(PackageDifference {
+            field,
+            committed,
+            package,
+        });
+    }
+}
+
+fn show_hash(hash: &Hash) -> String {
+    bs58::encode(hash).into_string()
+}
+
+fn show_debug<T: fmt::Debug>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+/// Compare the package committed in a proof's public values with a witness package.
+///
+/// Returns every differing field: the window, each slot's data, each account
+/// delta proof and each vote. An empty result means the proof was generated
+/// from this package.
+pub fn diff_packages(
+    committed: &ConsensusPackage,
+    package: &ConsensusPackage,
+) -> Vec<PackageDifference> {
+    let mut differ = Differ {
+        differences: Vec::new(),
+    };
+    differ.value(
+        "status".into(),
+        &committed.status,
+        &package.status,
+        show_debug,
+    );
+    differ.value(
+        "first_slot".into(),
+        &committed.first_slot,
+        &package.first_slot,
+        u64::to_string,
+    );
+    differ.value(
+        "last_slot".into(),
+        &committed.last_slot,
+        &package.last_slot,
+        u64::to_string,
+    );
+
+    let slots: BTreeSet<u64> = committed
+        .slot_data
+        .keys()
+        .chain(package.slot_data.keys())
+        .copied()
+        .collect();
+    for slot in slots {
+        let field = format!("slot_data[{}]", slot);
+        let (committed, package) =
+            match (committed.slot_data.get(&slot), package.slot_data.get(&slot)) {
+                (Some(committed), Some(package)) => (committed, package),
+                (committed, package) => {
+                    let show = |data: Option<&SlotData>| data.map(|_| "slot data".to_string());
+                    differ.presence(field, show(committed), show(package));
+                    continue;
+                }
+            };
+        differ.value(
+            format!("{}.bank_hash", field),
+            &committed.bank_hash,
+            &package.bank_hash,
+            |hash| hash.as_ref().map_or_else(|| "none".into(), show_hash),
+        );
+        differ.value(
+            format!("{}.num_sigs", field),
+            &committed.num_sigs,
+            &package.num_sigs,
+            u64::to_string,
+        );
+        differ.value(
+            format!("{}.account_delta_root", field),
+            &committed.account_delta_root,
+            &package.account_delta_root,
+            show_hash,
+        );
+        differ.value(
+            format!("{}.parent_bankhash", field),
+            &committed.parent_bankhash,
+            &package.parent_bankhash,
+            show_hash,
+        );
+        differ.value(
+            format!("{}.blockhash", field),
+            &committed.blockhash,
+            &package.blockhash,
+            show_hash,
+        );
+    }
+
+    diff_account_proofs(&mut differ, committed, package);
+    diff_votes(&mut differ, committed, package);
+    differ.differences
+}
+
+fn diff_account_proofs(
+    differ: &mut Differ,
+    committed: &ConsensusPackage,
+    package: &ConsensusPackage,
+) {
+    let by_key =
+        |package: &ConsensusPackage| -> BTreeMap<(u64, Pubkey), (AccountDeltaEntry, MerkleProof)> {
+            package
+                .proofs
+                .iter()
+                .flat_map(|(slot, proofs)| {
+                    proofs
+                        .iter()
+                        .map(move |(pubkey, proof)| ((*slot, *pubkey), proof.clone()))
+                })
+                .collect()
+        };
+    let (committed, package) = (by_key(committed), by_key(package));
+    let keys: BTreeSet<_> = committed.keys().chain(package.keys()).copied().collect();
+
+    for key in keys {
+        let field = format!("proofs[{}][{}]", key.0, show_hash(&key.1));
+        let ((committed_entry, committed_proof), (package_entry, package_proof)) =
+            match (committed.get(&key), package.get(&key)) {
+                (Some(committed), Some(package)) => (committed, package),
+                (committed, package) => {
+                    let show = |proof: Option<&_>| proof.map(|_| "account proof".to_string());
+                    differ.presence(field, show(committed), show(package));
+                    continue;
+                }
+            };
+
+        let (committed, package) = (&committed_entry.account, &package_entry.account);
+        differ.value(
+            format!("{}.hash", field),
+            &committed_entry.hash,
+            &package_entry.hash,
+            show_hash,
+        );
+        differ.value(
+            format!("{}.lamports", field),
+            &committed.lamports,
+            &package.lamports,
+            u64::to_string,
+        );
+        differ.value(
+            format!("{}.owner", field),
+            &committed.owner,
+            &package.owner,
+            show_hash,
+        );
+        differ.value(
+            format!("{}.executable", field),
+            &committed.executable,
+            &package.executable,
+            bool::to_string,
+        );
+        differ.value(
+            format!("{}.rent_epoch", field),
+            &committed.rent_epoch,
+            &package.rent_epoch,
+            u64::to_string,
+        );
+        differ.value(
+            format!("{}.data", field),
+            &committed.data,
+            &package.data,
+            |data| {
+                format!(
+                    "{} bytes ({})",
+                    data.len(),
+                    hex::encode(Sha256::digest(data))
+                )
+            },
+        );
+        differ.value(
+            format!("{}.write_version", field),
+            &committed.write_version,
+            &package.write_version,
+            u64::to_string,
+        );
+        differ.value(
+            format!("{}.merkle_proof", field),
+            committed_proof,
+            package_proof,
+            |proof| format!("path {:?}", proof.path),
+        );
+    }
+}
+
+fn diff_votes(differ: &mut Differ, committed: &ConsensusPackage, package: &ConsensusPackage) {
+    let show = |vote: &Vote| {
+        let tx = vote.transaction();
+        format!(
+            "vote by {} for slot {} ({})",
+            show_hash(&tx.voter_pubkey),
+            tx.vote_for_slot,
+            bs58::encode(&tx.signature).into_string()
+        )
+    };
+
+    let count = committed.votes.len().max(package.votes.len());
+    for index in 0..count {
+        let field = format!("votes[{}]", index);
+        match (committed.votes.get(index), package.votes.get(index)) {
+            (Some(committed), Some(package)) => differ.value(field, committed, package, show),
+            (committed, package) => differ.presence(field, committed.map(show), package.map(show)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_package() -> ConsensusPackage {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/package_solana.json");
+        load_package(Path::new(path)).unwrap()
+    }
+
+    fn issues(package: &ConsensusPackage, name: &str) -> Vec<PackageIssue> {
+        check_package(package)
+            .into_iter()
+            .find(|check| check.name == name)
+            .unwrap()
+            .issues
+    }
+
+    #[test]
+    fn sample_package_passes_every_check() {
+        for check in check_package(&sample_package()) {
+            assert!(check.passed(), "{}: {:?}", check.name, check.issues);
+        }
+    }
+
+    #[test]
+    fn reports_missing_slot_ranges() {
+        let mut package = sample_package();
+        let slot = package.first_slot;
+        package.first_slot = slot - 2;
+        package.last_slot = slot + 3;
+
+        assert_eq!(
+            issues(&package, "slot_completeness"),
+            vec![
+                PackageIssue::MissingSlotData {
+                    first_slot: slot - 2,
+                    last_slot: slot - 1,
+                },
+                PackageIssue::MissingSlotData {
+                    first_slot: slot + 1,
+                    last_slot: slot + 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_huge_window_as_one_range() {
+        let mut package = sample_package();
+        package.last_slot = u64::MAX;
+
+        assert_eq!(
+            issues(&package, "slot_completeness"),
+            vec![PackageIssue::MissingSlotData {
+                first_slot: package.first_slot + 1,
+                last_slot: u64::MAX,
+            }]
+        );
+    }
+
+    #[test]
+    fn checks_bank_hash_chain_across_slots() {
+        let mut package = sample_package();
+        let slot = package.first_slot;
+        let mut data = package.slot_data[&slot].clone();
+        for (i, next) in (slot + 1..=slot + 3).enumerate() {
+            data.parent_bankhash = data.bank_hash.unwrap();
+            data.bank_hash = Some([i as u8 + 1; 32]);
+            package.slot_data.insert(next, data.clone());
+        }
+        assert_eq!(check_bank_hash_chain(&package), vec![]);
+
+        package
+            .slot_data
+            .get_mut(&(slot + 2))
+            .unwrap()
+            .parent_bankhash = [9; 32];
+        package.slot_data.get_mut(&(slot + 3)).unwrap().bank_hash = None;
+        assert_eq!(
+            check_bank_hash_chain(&package),
+            vec![PackageIssue::ParentBankHash {
+                slot: slot + 2,
+                parent_slot: slot + 1,
+                expected: [1; 32],
+                actual: [9; 32],
+            }]
+        );
+
+        package.slot_data.get_mut(&(slot + 1)).unwrap().bank_hash = None;
+        assert_eq!(
+            check_bank_hash_chain(&package),
+            vec![PackageIssue::MissingBankHash { slot: slot + 1 }]
+        );
+    }
+
+    #[test]
+    fn sample_package_has_no_differences_with_itself() {
+        let package = sample_package();
+        assert_eq!(diff_packages(&package, &package), vec![]);
+    }
+
+    #[test]
+    fn reports_changed_slot_field() {
+        let committed = sample_package();
+        let mut package = committed.clone();
+        let slot = package.first_slot;
+        package.slot_data.get_mut(&slot).unwrap().num_sigs += 1;
+
+        let num_sigs = committed.slot_data[&slot].num_sigs;
+        assert_eq!(
+            diff_packages(&committed, &package),
+            vec![PackageDifference {
+                field: format!("slot_data[{}].num_sigs", slot),
+                committed: Some(num_sigs.to_string()),
+                package: Some((num_sigs + 1).to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_changed_proof_entry() {
+        let committed = sample_package();
+        let mut package = committed.clone();
+        let (&slot, proofs) = package.proofs.iter_mut().next().unwrap();
+        let (pubkey, (entry, _)) = &mut proofs[0];
+        let pubkey = *pubkey;
+        entry.account.lamports += 1;
+
+        let differences = diff_packages(&committed, &package);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(
+            differences[0].field,
+            format!("proofs[{}][{}].lamports", slot, show_hash(&pubkey))
+        );
+    }
+
+    #[test]
+    fn reports_changed_and_missing_votes() {
+        let committed = sample_package();
+        let mut package = committed.clone();
+        match &mut package.votes[0] {
+            Vote::CompactUpdateVoteState(tx) | Vote::TowerSync(tx) => tx.signature[0] ^= 0x01,
+        }
+        package.votes.pop();
+
+        let fields: Vec<_> = diff_packages(&committed, &package)
+            .into_iter()
+            .map(|difference| (difference.field, difference.package.is_some()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("votes[0]".to_string(), true),
+                (format!("votes[{}]", committed.votes.len() - 1), false),
+            ]
+        );
+    }
+}
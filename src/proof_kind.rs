@@ -0,0 +1,82 @@
+use crate::error::VerifyError;
+#[cfg(feature = "prover")]
+use sp1_sdk::SP1Proof;
+use std::fmt;
+
+/// The SP1 proof variant contained in a proof file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofKind {
+    /// Groth16 proof over BN254
+    Groth16,
+    /// Plonk proof over BN254
+    Plonk,
+    /// Compressed (recursive STARK) proof
+    Compressed,
+    /// Core (per-shard STARK) proof
+    Core,
+}
+
+impl ProofKind {
+    /// The variant of a proof
+    #[cfg(feature = "prover")]
+    pub fn of(proof: &SP1Proof) -> Self {
+        match proof {
+            SP1Proof::Groth16(_) => ProofKind::Groth16,
+            SP1Proof::Plonk(_) => ProofKind::Plonk,
+            SP1Proof::Compressed(_) => ProofKind::Compressed,
+            SP1Proof::Core(_) => ProofKind::Core,
+        }
+    }
+
+    /// Check that a proof is of the expected variant, if one is required
+    pub fn check(expected: Option<ProofKind>, actual: ProofKind) -> Result<(), VerifyError> {
+        match expected {
+            Some(expected) if expected != actual => Err(VerifyError::UnexpectedProofKind {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ProofKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProofKind::Groth16 => "Groth16",
+            ProofKind::Plonk => "Plonk",
+            ProofKind::Compressed => "Compressed",
+            ProofKind::Core => "Core",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_any_kind_without_requirement() {
+        for kind in [
+            ProofKind::Groth16,
+            ProofKind::Plonk,
+            ProofKind::Compressed,
+            ProofKind::Core,
+        ] {
+            assert_eq!(ProofKind::check(None, kind), Ok(()));
+            assert_eq!(ProofKind::check(Some(kind), kind), Ok(()));
+        }
+    }
+
+    #[test]
+    fn rejects_other_kind() {
+        assert_eq!(
+            ProofKind::check(Some(ProofKind::Plonk), ProofKind::Groth16),
+            Err(VerifyError::UnexpectedProofKind {
+                expected: "Plonk".to_string(),
+                actual: "Groth16".to_string(),
+            })
+        );
+    }
+}
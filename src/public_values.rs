@@ -0,0 +1,236 @@
+//! Typed view of the consensus data committed by the Twine Solana program.
+//!
+//! The program commits the bincode encoding of the [`ConsensusPackage`] it
+//! checked, followed by a `bool` result flag. The same types deserialize the
+//! JSON witness packages produced by the collector (`package_solana.json`).
+
+use crate::error::VerifyError;
+use crate::message::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// A 32-byte Solana hash (bank hash, blockhash, account hash, ...)
+pub type Hash = [u8; 32];
+
+/// A 32-byte Solana public key
+pub type Pubkey = [u8; 32];
+
+/// Collection status of a consensus package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PackageStatus {
+    /// Slot data has not been fetched yet
+    Pending,
+    /// Some but not all of the slot data has been fetched
+    InProgress,
+    /// All slot data, account proofs and votes have been fetched
+    Complete,
+}
+
+/// Bank hash components for a single slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotData {
+    /// Bank hash of the slot, if it was observed
+    pub bank_hash: Option<Hash>,
+    /// Number of signatures processed in the slot
+    pub num_sigs: u64,
+    /// Merkle root of all accounts modified in the slot
+    pub account_delta_root: Hash,
+    /// Bank hash of the parent slot
+    pub parent_bankhash: Hash,
+    /// Last blockhash of the slot
+    pub blockhash: Hash,
+}
+
+/// Account state as stored by the validator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    /// Address of the account
+    pub pubkey: Pubkey,
+    /// Balance in lamports
+    pub lamports: u64,
+    /// Program that owns the account
+    pub owner: Pubkey,
+    /// Whether the account holds an executable program
+    pub executable: bool,
+    /// Epoch at which the account next owes rent
+    pub rent_epoch: u64,
+    /// Account data (program state)
+    pub data: Vec<u8>,
+    /// Write version of this account update
+    pub write_version: u64,
+    /// Slot in which the account was modified
+    pub slot: u64,
+}
+
+/// An account included in a slot's accounts delta
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDeltaEntry {
+    /// Address of the account
+    pub pubkey: Pubkey,
+    /// Account hash used as the Merkle leaf
+    pub hash: Hash,
+    /// The account state
+    pub account: Account,
+}
+
+/// Merkle path from an account hash to the accounts delta root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// Position of the node among its siblings, from the leaf upwards
+    pub path: Vec<usize>,
+    /// Sibling hashes at each level, from the leaf upwards
+    pub siblings: Vec<Vec<Hash>>,
+}
+
+/// An account delta proof keyed by account address
+pub type AccountDeltaProof = (Pubkey, (AccountDeltaEntry, MerkleProof));
+
+/// A signed vote transaction observed for a slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteTransaction {
+    /// Slot being voted for
+    pub vote_for_slot: u64,
+    /// Bank hash being voted for
+    pub vote_for_hash: Hash,
+    /// Number of lockouts in the vote
+    pub lockouts: u64,
+    /// The signed transaction message
+    pub message: Message,
+    /// Ed25519 signature over the serialized message
+    pub signature: Vec<u8>,
+    /// Validator vote authority that signed the message
+    pub voter_pubkey: Pubkey,
+}
+
+/// A vote record, tagged by the vote instruction it carries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Vote {
+    /// A `CompactUpdateVoteState` vote
+    CompactUpdateVoteState(VoteTransaction),
+    /// A `TowerSync` vote
+    TowerSync(VoteTransaction),
+}
+
+impl Vote {
+    /// The vote transaction regardless of the instruction kind
+    pub fn transaction(&self) -> &VoteTransaction {
+        match self {
+            Vote::CompactUpdateVoteState(tx) | Vote::TowerSync(tx) => tx,
+        }
+    }
+}
+
+/// The Solana consensus data checked by the zkVM program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsensusPackage {
+    /// Collection status of the package
+    pub status: PackageStatus,
+    /// First slot of the consensus window
+    pub first_slot: u64,
+    /// Last slot of the consensus window
+    pub last_slot: u64,
+    /// Bank hash components for each slot
+    pub slot_data: BTreeMap<u64, SlotData>,
+    /// Account delta proofs for each slot
+    pub proofs: BTreeMap<u64, Vec<AccountDeltaProof>>,
+    /// Validator votes for the window
+    pub votes: Vec<Vote>,
+}
+
+/// Decoded public values of a consensus proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsensusOutput {
+    /// The consensus package the program checked
+    pub package: ConsensusPackage,
+    /// Result flag committed after the package
+    pub valid: bool,
+}
+
+impl ConsensusOutput {
+    /// Check that the program committed a valid result for the package
+    pub fn check_valid(&self) -> Result<(), VerifyError> {
+        if !self.valid {
+            return Err(VerifyError::ConsensusRejected {
+                first_slot: self.package.first_slot,
+                last_slot: self.package.last_slot,
+            });
+        }
+        Ok(())
+    }
+
+    /// Bank hash committed for a slot, if any
+    pub fn bank_hash(&self, slot: u64) -> Option<Hash> {
+        self.package
+            .slot_data
+            .get(&slot)
+            .and_then(|data| data.bank_hash)
+    }
+
+    /// Number of account delta proofs across all slots
+    pub fn account_count(&self) -> usize {
+        self.package.proofs.values().map(Vec::len).sum()
+    }
+}
+
+/// Decode the public values committed by the Twine Solana consensus program
+pub fn decode_public_values(bytes: &[u8]) -> Result<ConsensusOutput, Box<dyn Error>> {
+    let mut reader = bytes;
+    let package: ConsensusPackage = bincode::deserialize_from(&mut reader)?;
+    let valid: bool = bincode::deserialize_from(&mut reader)?;
+
+    if !reader.is_empty() {
+        return Err(format!(
+            "{} trailing bytes after the committed consensus output",
+            reader.len()
+        )
+        .into());
+    }
+
+    Ok(ConsensusOutput { package, valid })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::NativeProof;
+    use std::fs;
+
+    fn sample_public_values() -> Vec<u8> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/groth16_proof.json");
+        let proof = NativeProof::from_json(&fs::read(path).unwrap()).unwrap();
+        proof.public_values().to_vec()
+    }
+
+    #[test]
+    fn decodes_sample_public_values() {
+        let public_values = sample_public_values();
+        let output = decode_public_values(&public_values).unwrap();
+
+        assert!(output.valid);
+        assert_eq!(bincode::serialize(&output).unwrap(), public_values);
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/package_solana.json");
+        let package: ConsensusPackage = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        assert_eq!(output.package, package);
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut public_values = sample_public_values();
+        public_values.push(0);
+        assert!(decode_public_values(&public_values).is_err());
+    }
+
+    #[test]
+    fn reports_committed_invalid_result() {
+        let mut public_values = sample_public_values();
+        *public_values.last_mut().unwrap() = 0;
+        let output = decode_public_values(&public_values).unwrap();
+
+        assert!(matches!(
+            output.check_valid(),
+            Err(VerifyError::ConsensusRejected { .. })
+        ));
+    }
+}
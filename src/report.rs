@@ -0,0 +1,118 @@
+use crate::error::VerifyError;
+use crate::outcome::{CheckResult, VerificationOutcome};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// An input file and the SHA-256 of its contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InputFile {
+    pub path: String,
+    /// Hex SHA-256 of the file contents
+    pub sha256: String,
+}
+
+impl InputFile {
+    /// Describe a file from its path and contents
+    pub fn new(path: impl Into<String>, contents: &[u8]) -> Self {
+        Self {
+            path: path.into(),
+            sha256: hex::encode(Sha256::digest(contents)),
+        }
+    }
+
+    /// Read a file, returning its description and contents
+    pub fn read(path: &Path) -> io::Result<(Self, Vec<u8>)> {
+        let contents = fs::read(path)?;
+        Ok((Self::new(path.display().to_string(), &contents), contents))
+    }
+}
+
+/// Time spent in each phase, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Timings {
+    /// Reading and parsing the proof and verification key
+    pub load_ms: f64,
+    /// Running the verification checks
+    pub verify_ms: f64,
+    /// Everything, including setup
+    pub total_ms: f64,
+}
+
+impl Timings {
+    /// Convert a duration to fractional milliseconds
+    pub fn millis(duration: Duration) -> f64 {
+        duration.as_secs_f64() * 1000.0
+    }
+}
+
+/// Why verification did not succeed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportError {
+    /// Stable error code, see [`VerifyError::code`]
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl From<&VerifyError> for ReportError {
+    fn from(error: &VerifyError) -> Self {
+        Self {
+            code: error.code(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Machine-readable report of a single verification
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerificationReport {
+    /// Whether the proof was accepted
+    pub valid: bool,
+    pub proof: Option<InputFile>,
+    pub vkey: Option<InputFile>,
+    /// Proof variant, such as `Groth16`
+    pub proof_kind: Option<String>,
+    pub sp1_version: Option<String>,
+    /// Program vkey hash (`bytes32()` form)
+    pub vkey_hash: Option<String>,
+    /// Allow-list label of the program version
+    pub program_version: Option<String>,
+    pub first_slot: Option<u64>,
+    pub last_slot: Option<u64>,
+    /// Committed bank hashes by slot, in base58
+    pub bank_hashes: BTreeMap<u64, String>,
+    /// Every check in the order it was run
+    pub checks: Vec<CheckResult>,
+    pub timings: Timings,
+    pub error: Option<ReportError>,
+}
+
+impl VerificationReport {
+    /// Record the verdict, checks and decoded consensus data of an outcome
+    pub fn record_outcome(&mut self, outcome: &VerificationOutcome) {
+        self.valid = outcome.is_valid();
+        self.sp1_version = Some(outcome.sp1_version.clone());
+        self.vkey_hash = Some(outcome.vkey_hash.clone());
+        self.program_version = outcome.program_version.clone();
+        self.checks = outcome.checks.clone();
+        self.error = outcome.error().map(ReportError::from);
+
+        if let Ok(output) = outcome.consensus() {
+            let package = &output.package;
+            self.first_slot = Some(package.first_slot);
+            self.last_slot = Some(package.last_slot);
+            self.bank_hashes = package
+                .slot_data
+                .iter()
+                .filter_map(|(slot, data)| {
+                    data.bank_hash
+                        .map(|hash| (*slot, bs58::encode(hash).into_string()))
+                })
+                .collect();
+        }
+    }
+}
@@ -0,0 +1,394 @@
+//! HTTP verification service.
+//!
+//! Keeps the prover client and verification keys loaded and answers:
+//!
+//! - `POST /verify[?vkey=<id>]` with a proof body (JSON or bincode, optionally
+//!   compressed): the verification report
+//! - `GET /vkeys`: the loaded verification keys
+//! - `GET /slots/{slot}`: the stored consensus data of a slot, when a store is configured
+
+use crate::error::VerifyError;
+use crate::input::{decompress, parse_proof, DEFAULT_MAX_DECOMPRESSED_BYTES};
+use crate::outcome::{CheckResult, CheckStatus};
+use crate::proof_kind::ProofKind;
+use crate::report::{InputFile, ReportError, Timings, VerificationReport};
+use crate::store::{SlotStore, StoreRecord};
+use crate::verifier::Verifier;
+use serde_json::{json, Value};
+use sp1_sdk::{HashableKey, SP1VerifyingKey};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Largest accepted request body, unless configured otherwise
+pub const DEFAULT_MAX_BODY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Verification service state shared by the worker threads
+pub struct VerifyServer {
+    verifier: Verifier,
+    /// Verification keys by id
+    vkeys: BTreeMap<String, SP1VerifyingKey>,
+    /// Id of the key used when a request names none
+    default_vkey: Option<String>,
+    store: Option<Mutex<SlotStore>>,
+    /// Largest accepted request body as sent
+    max_body_bytes: u64,
+    /// Largest accepted request body after decompression
+    max_decompressed_bytes: u64,
+}
+
+impl VerifyServer {
+    /// Create a service around a configured verifier
+    pub fn new(verifier: Verifier) -> Self {
+        Self {
+            verifier,
+            vkeys: BTreeMap::new(),
+            default_vkey: None,
+            store: None,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_decompressed_bytes: DEFAULT_MAX_DECOMPRESSED_BYTES,
+        }
+    }
+
+    /// Make a verification key available under an id. The first key added is the default.
+    pub fn with_vkey(mut self, id: impl Into<String>, vk: SP1VerifyingKey) -> Self {
+        let id = id.into();
+        self.default_vkey.get_or_insert_with(|| id.clone());
+        self.vkeys.insert(id, vk);
+        self
+    }
+
+    /// Record verified proofs in a light-client store and serve its slots
+    pub fn with_store(mut self, store: SlotStore) -> Self {
+        self.store = Some(Mutex::new(store));
+        self
+    }
+
+    /// Answer 413 to request bodies of more than `limit` bytes
+    pub fn with_max_body_bytes(mut self, limit: u64) -> Self {
+        self.max_body_bytes = limit;
+        self
+    }
+
+    /// Reject request bodies that decompress to more than `limit` bytes
+    pub fn with_max_decompressed_bytes(mut self, limit: u64) -> Self {
+        self.max_decompressed_bytes = limit;
+        self
+    }
+
+    /// Listen on `addr` and handle requests on `threads` worker threads until the process exits
+    pub fn serve(self, addr: &str, threads: usize) -> Result<(), Box<dyn Error>> {
+        self.serve_on(Server::http(addr)?, threads)
+    }
+
+    /// Handle requests to a listening server on `threads` worker threads until the process exits
+    pub fn serve_on(self, server: Server, threads: usize) -> Result<(), Box<dyn Error>> {
+        let server = Arc::new(server);
+        let service = Arc::new(self);
+
+        let workers: Vec<_> = (0..threads.max(1))
+            .map(|_| {
+                let server = Arc::clone(&server);
+                let service = Arc::clone(&service);
+                thread::spawn(move || {
+                    while let Ok(mut request) = server.recv() {
+                        let (status, body) = service.handle(&mut request);
+                        let response = Response::from_string(body.to_string())
+                            .with_status_code(status)
+                            .with_header(
+                                Header::from_bytes("Content-Type", "application/json")
+                                    .expect("static header is valid"),
+                            );
+                        // The client may have gone away; nothing to do about it
+                        let _ = request.respond(response);
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().map_err(|_| "server worker thread panicked")?;
+        }
+        Ok(())
+    }
+
+    /// Route a request to its handler, returning the status code and JSON body
+    fn handle(&self, request: &mut Request) -> (u16, Value) {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+        match (method, path) {
+            (Method::Post, "/verify") => {
+                let too_large = (
+                    413,
+                    json!({
+                        "error": format!("request body exceeds {} bytes", self.max_body_bytes)
+                    }),
+                );
+                if request
+                    .body_length()
+                    .is_some_and(|len| len as u64 > self.max_body_bytes)
+                {
+                    return too_large;
+                }
+
+                // Read one byte past the limit to tell a body at the limit from a longer one
+                let mut body = Vec::new();
+                if let Err(e) = request
+                    .as_reader()
+                    .take(self.max_body_bytes.saturating_add(1))
+                    .read_to_end(&mut body)
+                {
+                    return error_response(400, &VerifyError::from(e));
+                }
+                if body.len() as u64 > self.max_body_bytes {
+                    return too_large;
+                }
+                self.verify(&body, query_param(query, "vkey"))
+            }
+            (Method::Get, "/vkeys") => (200, self.list_vkeys()),
+            (Method::Get, path) if path.starts_with("/slots/") => {
+                self.slot(&path["/slots/".len()..])
+            }
+            _ => (404, json!({ "error": "not found" })),
+        }
+    }
+
+    fn verify(&self, body: &[u8], vkey_id: Option<&str>) -> (u16, Value) {
+        let start = Instant::now();
+        let Some(id) = vkey_id.or(self.default_vkey.as_deref()) else {
+            return (404, json!({ "error": "no verification key loaded" }));
+        };
+        let Some(vk) = self.vkeys.get(id) else {
+            return (404, json!({ "error": format!("unknown vkey id '{}'", id) }));
+        };
+
+        let mut report = VerificationReport {
+            proof: Some(InputFile::new("request body", body)),
+            ..Default::default()
+        };
+        let proof = match decompress(body.to_vec(), self.max_decompressed_bytes)
+            .and_then(|bytes| parse_proof(&bytes))
+        {
+            Ok(proof) => proof,
+            Err(error) => return error_response(400, &error),
+        };
+        report.proof_kind = Some(ProofKind::of(&proof.proof).to_string());
+        report.timings.load_ms = Timings::millis(start.elapsed());
+
+        let verify_start = Instant::now();
+        let outcome = self.verifier.verify(&proof, vk);
+        report.timings.verify_ms = Timings::millis(verify_start.elapsed());
+        report.record_outcome(&outcome);
+
+        if let (true, Some(store)) = (outcome.is_valid(), &self.store) {
+            let proof_sha256 = report
+                .proof
+                .as_ref()
+                .map(|file| file.sha256.clone())
+                .unwrap_or_default();
+            let recorded = StoreRecord::from_outcome(&outcome, proof_sha256)
+                .map_err(|e| -> Box<dyn Error> { e.into() })
+                .and_then(|record| lock(store).record(record));
+            let status = match recorded {
+                Ok(_) => CheckStatus::Passed,
+                Err(e) => {
+                    report.valid = false;
+                    report.error = Some(match e.downcast_ref::<VerifyError>() {
+                        Some(error) => ReportError::from(error),
+                        None => ReportError::from(&VerifyError::Internal(e.to_string())),
+                    });
+                    CheckStatus::Failed
+                }
+            };
+            report.checks.push(CheckResult {
+                name: "store",
+                status,
+            });
+        }
+
+        report.timings.total_ms = Timings::millis(start.elapsed());
+        (200, json!(report))
+    }
+
+    fn list_vkeys(&self) -> Value {
+        let allowed = self.verifier.allowed_vkeys();
+        let vkeys: Vec<Value> = self
+            .vkeys
+            .iter()
+            .map(|(id, vk)| {
+                let hash = vk.bytes32();
+                let label = allowed
+                    .and_then(|allowed| allowed.lookup(&hash))
+                    .map(|vkey| vkey.label.clone());
+                json!({
+                    "id": id,
+                    "vkey_hash": hash,
+                    "label": label,
+                    "default": self.default_vkey.as_deref() == Some(id.as_str()),
+                })
+            })
+            .collect();
+        json!({ "vkeys": vkeys })
+    }
+
+    fn slot(&self, slot: &str) -> (u16, Value) {
+        let Ok(slot) = slot.parse::<u64>() else {
+            return (400, json!({ "error": format!("invalid slot '{}'", slot) }));
+        };
+        let Some(store) = &self.store else {
+            return (404, json!({ "error": "no store configured" }));
+        };
+
+        let store = lock(store);
+        match store.slot(slot) {
+            Some((stored, record)) => (
+                200,
+                json!({
+                    "slot": stored.slot,
+                    "bank_hash": stored.bank_hash.map(|hash| bs58::encode(hash).into_string()),
+                    "parent_bankhash": bs58::encode(stored.parent_bankhash).into_string(),
+                    "blockhash": bs58::encode(stored.blockhash).into_string(),
+                    "account_delta_root": bs58::encode(stored.account_delta_root).into_string(),
+                    "proof_sha256": record.proof_sha256,
+                    "vkey_hash": record.vkey_hash,
+                    "recorded_at": record.recorded_at,
+                }),
+            ),
+            None => (
+                404,
+                json!({ "error": format!("slot {} not verified", slot) }),
+            ),
+        }
+    }
+}
+
+/// Lock the store, recovering it if a worker panicked while holding it
+fn lock(store: &Mutex<SlotStore>) -> std::sync::MutexGuard<'_, SlotStore> {
+    store
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn error_response(status: u16, error: &VerifyError) -> (u16, Value) {
+    (
+        status,
+        json!({ "valid": false, "error": ReportError::from(error) }),
+    )
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::load_vkey;
+    use std::fs;
+    use std::io::Write;
+    use std::net::{SocketAddr, TcpStream};
+    use std::path::{Path, PathBuf};
+
+    fn data(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("data")
+            .join(name)
+    }
+
+    /// A service with the sample vkey
+    fn sample_service() -> VerifyServer {
+        VerifyServer::new(Verifier::from_env())
+            .with_vkey("default", load_vkey(&data("vkey.json")).unwrap())
+    }
+
+    /// Serve on a free local port
+    fn start(service: VerifyServer) -> SocketAddr {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        thread::spawn(move || service.serve_on(server, 1).map_err(|e| e.to_string()));
+        addr
+    }
+
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &[u8]) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            method,
+            path,
+            addr,
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn serves_verification_vkeys_and_slots() {
+        let store =
+            std::env::temp_dir().join(format!("verify-server-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&store);
+        let addr = start(sample_service().with_store(SlotStore::open(&store).unwrap()));
+        let proof = fs::read(data("groth16_proof.json")).unwrap();
+
+        let (status, body) = request(addr, "POST", "/verify", &proof);
+        assert_eq!(status, 200);
+        assert_eq!(body["valid"], true);
+        assert_eq!(body["first_slot"], 326251073);
+
+        let (status, body) = request(addr, "POST", "/verify", b"not a proof");
+        assert_eq!(status, 400);
+        assert_eq!(body["error"]["code"], "malformed_proof_json");
+
+        let (status, _) = request(addr, "POST", "/verify?vkey=unknown", &proof);
+        assert_eq!(status, 404);
+
+        let (status, body) = request(addr, "GET", "/vkeys", b"");
+        assert_eq!(status, 200);
+        assert_eq!(body["vkeys"][0]["id"], "default");
+        assert_eq!(body["vkeys"][0]["default"], true);
+
+        let (status, body) = request(addr, "GET", "/slots/326251073", b"");
+        assert_eq!(status, 200);
+        assert_eq!(
+            body["bank_hash"],
+            "6eVY82E3Lv3W91S8gjx3ceYYj8tbaB3JYxqgaYCDbur4"
+        );
+
+        let (status, _) = request(addr, "GET", "/slots/1", b"");
+        assert_eq!(status, 404);
+        let (status, _) = request(addr, "GET", "/slots/latest", b"");
+        assert_eq!(status, 400);
+
+        fs::remove_file(&store).unwrap();
+    }
+
+    #[test]
+    fn rejects_oversized_body() {
+        let addr = start(sample_service().with_max_body_bytes(16));
+
+        let (status, body) = request(addr, "POST", "/verify", &[b'x'; 17]);
+        assert_eq!(status, 413);
+        assert_eq!(body["error"], "request body exceeds 16 bytes");
+
+        let (status, body) = request(addr, "POST", "/verify", &[b'x'; 16]);
+        assert_eq!(status, 400);
+        assert_eq!(body["error"]["code"], "malformed_proof_json");
+    }
+}
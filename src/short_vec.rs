@@ -0,0 +1,184 @@
+//! Serde helpers for Solana's compact-u16 length prefixed sequences.
+//!
+//! Solana messages encode sequence lengths as a little-endian base-128
+//! varint of at most three bytes. The length is serialized as a tuple of
+//! bytes followed by the elements, which is why the JSON witness files show
+//! arrays like `[[3], key, key, key]`. The same implementation reads both the
+//! bincode encoded public values and the JSON packages.
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Maximum number of bytes in a compact-u16 encoding
+const MAX_ENCODING_LENGTH: usize = 3;
+
+/// A `u16` serialized with the compact-u16 varint encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortU16(pub u16);
+
+impl Serialize for ShortU16 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_tuple(1)?;
+
+        let mut rem = self.0;
+        loop {
+            let mut elem = (rem & 0x7f) as u8;
+            rem >>= 7;
+            if rem == 0 {
+                seq.serialize_element(&elem)?;
+                break;
+            }
+            elem |= 0x80;
+            seq.serialize_element(&elem)?;
+        }
+
+        seq.end()
+    }
+}
+
+struct ShortU16Visitor;
+
+impl<'de> Visitor<'de> for ShortU16Visitor {
+    type Value = ShortU16;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a compact-u16 length")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ShortU16, A::Error> {
+        let mut value: u16 = 0;
+
+        for i in 0..MAX_ENCODING_LENGTH {
+            let byte: u8 = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+
+            // The third byte may only carry the top two bits of the value
+            if i == MAX_ENCODING_LENGTH - 1 && byte > 0x03 {
+                return Err(de::Error::custom("compact-u16 length overflows u16"));
+            }
+
+            value |= u16::from(byte & 0x7f) << (i * 7);
+
+            if byte & 0x80 == 0 {
+                if i > 0 && byte == 0 {
+                    return Err(de::Error::custom(
+                        "compact-u16 length is not minimally encoded",
+                    ));
+                }
+                return Ok(ShortU16(value));
+            }
+        }
+
+        Err(de::Error::custom("compact-u16 length is too long"))
+    }
+}
+
+impl<'de> Deserialize<'de> for ShortU16 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ShortU16, D::Error> {
+        deserializer.deserialize_tuple(MAX_ENCODING_LENGTH, ShortU16Visitor)
+    }
+}
+
+/// Serialize a slice with a compact-u16 length prefix
+pub fn serialize<S: Serializer, T: Serialize>(
+    elements: &[T],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let len = u16::try_from(elements.len())
+        .map_err(|_| serde::ser::Error::custom("length larger than u16"))?;
+
+    // The tuple length is not known to bincode or serde_json up front
+    let mut seq = serializer.serialize_tuple(1)?;
+    seq.serialize_element(&ShortU16(len))?;
+    for element in elements {
+        seq.serialize_element(element)?;
+    }
+    seq.end()
+}
+
+struct ShortVecVisitor<T> {
+    _marker: PhantomData<T>,
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for ShortVecVisitor<T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a compact-u16 length prefixed sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+        let len: ShortU16 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let len = usize::from(len.0);
+
+        let mut result = Vec::with_capacity(len);
+        for i in 0..len {
+            let element = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i + 1, &self))?;
+            result.push(element);
+        }
+        Ok(result)
+    }
+}
+
+/// Deserialize a compact-u16 length prefixed sequence
+pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<Vec<T>, D::Error> {
+    let visitor = ShortVecVisitor {
+        _marker: PhantomData,
+    };
+    deserializer.deserialize_tuple(usize::MAX, visitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bytes: &[u8]) -> Result<u16, bincode::Error> {
+        bincode::deserialize::<ShortU16>(bytes).map(|len| len.0)
+    }
+
+    #[test]
+    fn round_trips_boundary_values() {
+        for (value, encoded) in [
+            (0, &[0x00][..]),
+            (0x7f, &[0x7f]),
+            (0x80, &[0x80, 0x01]),
+            (0x3fff, &[0xff, 0x7f]),
+            (0x4000, &[0x80, 0x80, 0x01]),
+            (u16::MAX, &[0xff, 0xff, 0x03]),
+        ] {
+            assert_eq!(bincode::serialize(&ShortU16(value)).unwrap(), encoded);
+            assert_eq!(decode(encoded).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn rejects_overlong_encoding() {
+        assert!(decode(&[0x80, 0x80, 0x80, 0x00]).is_err());
+    }
+
+    #[test]
+    fn rejects_non_minimal_encoding() {
+        assert!(decode(&[0x80, 0x00]).is_err());
+        assert!(decode(&[0xff, 0x80, 0x00]).is_err());
+    }
+
+    #[test]
+    fn rejects_third_byte_above_two_bits() {
+        assert!(decode(&[0xff, 0xff, 0x04]).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_encoding() {
+        assert!(decode(&[0x80]).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! Persistent record of verified Solana slots.
+//!
+//! The store is an append-only JSON lines file with one [`StoreRecord`] per
+//! verified proof. It is read into memory when opened and every accepted
+//! proof is appended and flushed to disk, so the file can be inspected with
+//! standard tools and survives crashes up to the last complete line.
+//!
+//! Once a bank hash has been accepted for a slot, a proof committing a
+//! different bank hash for that slot is refused.
+
+use crate::error::VerifyError;
+use crate::outcome::VerificationOutcome;
+use crate::public_values::Hash;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Consensus data of one slot from a verified proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredSlot {
+    pub slot: u64,
+    /// Bank hash of the slot, if the proof committed one
+    pub bank_hash: Option<Hash>,
+    pub parent_bankhash: Hash,
+    pub blockhash: Hash,
+    pub account_delta_root: Hash,
+}
+
+/// A verified proof as recorded in the store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreRecord {
+    /// Hex SHA-256 of the proof file
+    pub proof_sha256: String,
+    /// Program vkey hash the proof was verified against
+    pub vkey_hash: String,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub slots: Vec<StoredSlot>,
+    /// Unix time at which the proof was recorded, in seconds
+    pub recorded_at: u64,
+}
+
+impl StoreRecord {
+    /// Build a record from the outcome of a successful verification
+    pub fn from_outcome(
+        outcome: &VerificationOutcome,
+        proof_sha256: impl Into<String>,
+    ) -> Result<Self, VerifyError> {
+        let output = outcome
+            .consensus()
+            .map_err(|e| VerifyError::MalformedPublicValues(e.to_string()))?;
+        let package = output.package;
+
+        let slots = package
+            .slot_data
+            .iter()
+            .map(|(slot, data)| StoredSlot {
+                slot: *slot,
+                bank_hash: data.bank_hash,
+                parent_bankhash: data.parent_bankhash,
+                blockhash: data.blockhash,
+                account_delta_root: data.account_delta_root,
+            })
+            .collect();
+
+        Ok(Self {
+            proof_sha256: proof_sha256.into(),
+            vkey_hash: outcome.vkey_hash.clone(),
+            first_slot: package.first_slot,
+            last_slot: package.last_slot,
+            slots,
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        })
+    }
+}
+
+/// Light-client state: every slot accepted from a verified proof
+#[derive(Debug)]
+pub struct SlotStore {
+    path: PathBuf,
+    records: Vec<StoreRecord>,
+    /// Index of the record that provided each slot
+    slots: BTreeMap<u64, usize>,
+}
+
+impl SlotStore {
+    /// Open a store file, creating an empty store if it does not exist
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut store = Self {
+            path: path.to_path_buf(),
+            records: Vec::new(),
+            slots: BTreeMap::new(),
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(store),
+            Err(e) => return Err(e.into()),
+        };
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: StoreRecord = serde_json::from_str(line)
+                .map_err(|e| format!("{}:{}: {}", path.display(), i + 1, e))?;
+            store.index(record);
+        }
+
+        Ok(store)
+    }
+
+    fn index(&mut self, record: StoreRecord) {
+        let position = self.records.len();
+        for slot in &record.slots {
+            // Keep the first record that committed a bank hash for the slot
+            let replace = match self.slot(slot.slot) {
+                None => true,
+                Some((stored, _)) => stored.bank_hash.is_none() && slot.bank_hash.is_some(),
+            };
+            if replace {
+                self.slots.insert(slot.slot, position);
+            }
+        }
+        self.records.push(record);
+    }
+
+    /// All recorded proofs, oldest first
+    pub fn records(&self) -> &[StoreRecord] {
+        &self.records
+    }
+
+    /// The stored data of a slot and the record it came from
+    pub fn slot(&self, slot: u64) -> Option<(&StoredSlot, &StoreRecord)> {
+        let record = &self.records[*self.slots.get(&slot)?];
+        let stored = record.slots.iter().find(|stored| stored.slot == slot)?;
+        Some((stored, record))
+    }
+
+    /// Highest slot recorded so far
+    pub fn latest_slot(&self) -> Option<u64> {
+        self.slots.keys().next_back().copied()
+    }
+
+    /// Check that a record does not conflict with an accepted bank hash
+    pub fn check(&self, record: &StoreRecord) -> Result<(), VerifyError> {
+        for proposed in &record.slots {
+            let Some((stored, _)) = self.slot(proposed.slot) else {
+                continue;
+            };
+            if let (Some(stored_hash), Some(proposed_hash)) = (stored.bank_hash, proposed.bank_hash)
+            {
+                if stored_hash != proposed_hash {
+                    return Err(VerifyError::ConflictingBankHash {
+                        slot: proposed.slot,
+                        stored: bs58::encode(stored_hash).into_string(),
+                        proposed: bs58::encode(proposed_hash).into_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Append a record unless it conflicts with the store.
+    ///
+    /// Returns false if the same proof was already recorded.
+    pub fn record(&mut self, record: StoreRecord) -> Result<bool, Box<dyn Error>> {
+        if self
+            .records
+            .iter()
+            .any(|existing| existing.proof_sha256 == record.proof_sha256)
+        {
+            return Ok(false);
+        }
+        self.check(&record)?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        file.sync_data()?;
+
+        self.index(record);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A store path unique to the test, removed if left over from an earlier run
+    fn temp_store(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("slot-store-{}-{}.jsonl", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn record(proof_sha256: &str, slot: u64, bank_hash: Hash) -> StoreRecord {
+        StoreRecord {
+            proof_sha256: proof_sha256.to_string(),
+            vkey_hash: "0x00".to_string(),
+            first_slot: slot,
+            last_slot: slot,
+            slots: vec![StoredSlot {
+                slot,
+                bank_hash: Some(bank_hash),
+                parent_bankhash: [0; 32],
+                blockhash: [0; 32],
+                account_delta_root: [0; 32],
+            }],
+            recorded_at: 0,
+        }
+    }
+
+    #[test]
+    fn refuses_conflicting_bank_hash() {
+        let path = temp_store("conflict");
+        let mut store = SlotStore::open(&path).unwrap();
+        assert!(store.record(record("a", 10, [1; 32])).unwrap());
+
+        let error = store.record(record("b", 10, [2; 32])).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<VerifyError>(),
+            Some(VerifyError::ConflictingBankHash { slot: 10, .. })
+        ));
+        assert_eq!(store.records().len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn ignores_duplicate_proof() {
+        let path = temp_store("duplicate");
+        let mut store = SlotStore::open(&path).unwrap();
+        assert!(store.record(record("a", 10, [1; 32])).unwrap());
+
+        assert!(!store.record(record("a", 11, [2; 32])).unwrap());
+        assert_eq!(store.records().len(), 1);
+        assert_eq!(store.latest_slot(), Some(10));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rebuilds_index_on_reopen() {
+        let path = temp_store("reopen");
+        let mut store = SlotStore::open(&path).unwrap();
+        store.record(record("a", 10, [1; 32])).unwrap();
+        store.record(record("b", 20, [2; 32])).unwrap();
+        drop(store);
+
+        let mut store = SlotStore::open(&path).unwrap();
+        assert_eq!(store.records().len(), 2);
+        assert_eq!(store.latest_slot(), Some(20));
+        let (slot, record_of_slot) = store.slot(10).unwrap();
+        assert_eq!(slot.bank_hash, Some([1; 32]));
+        assert_eq!(record_of_slot.proof_sha256, "a");
+        assert!(store.record(record("c", 20, [3; 32])).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
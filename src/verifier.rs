@@ -0,0 +1,205 @@
+use crate::allowlist::VkeyAllowList;
+use crate::bank_hash::BankHashExtras;
+use crate::compat::{check_sp1_version, expected_circuit_version};
+use crate::digest::check_committed_values_digest;
+use crate::error::VerifyError;
+use crate::expect::ConsensusExpectations;
+use crate::groth16_vk::check_proof_points;
+use crate::input::{parse_proof_json, parse_vkey_json};
+use crate::outcome::{Checks, VerificationOutcome};
+use crate::proof_kind::ProofKind;
+use sp1_sdk::{
+    EnvProver, HashableKey, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1VerificationError,
+    SP1VerifyingKey,
+};
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Verifies Solana consensus proofs against a program verification key
+pub struct Verifier {
+    client: EnvProver,
+    expectations: ConsensusExpectations,
+    allowed_vkeys: Option<VkeyAllowList>,
+    proof_kind: Option<ProofKind>,
+    bank_hash_extras: Option<BTreeMap<u64, BankHashExtras>>,
+}
+
+impl Verifier {
+    /// Create a verifier backed by a prover client configured from the environment
+    pub fn from_env() -> Self {
+        Self {
+            client: ProverClient::from_env(),
+            expectations: ConsensusExpectations::default(),
+            allowed_vkeys: None,
+            proof_kind: None,
+            bank_hash_extras: None,
+        }
+    }
+
+    /// Only accept proofs of this variant
+    pub fn with_proof_kind(mut self, proof_kind: ProofKind) -> Self {
+        self.proof_kind = Some(proof_kind);
+        self
+    }
+
+    /// Only accept proofs for program vkeys in the allow-list
+    pub fn with_allowed_vkeys(mut self, allowed_vkeys: VkeyAllowList) -> Self {
+        self.allowed_vkeys = Some(allowed_vkeys);
+        self
+    }
+
+    /// The allow-list proofs are checked against, if any
+    pub fn allowed_vkeys(&self) -> Option<&VkeyAllowList> {
+        self.allowed_vkeys.as_ref()
+    }
+
+    /// Recompute every committed bank hash from its components, mixing in the
+    /// extras given for a slot. Slots without extras are assumed to need none.
+    pub fn with_bank_hash_check(mut self, extras: BTreeMap<u64, BankHashExtras>) -> Self {
+        self.bank_hash_extras = Some(extras);
+        self
+    }
+
+    /// Require verified proofs to commit to the expected consensus data
+    pub fn with_expectations(mut self, expectations: ConsensusExpectations) -> Self {
+        self.expectations = expectations;
+        self
+    }
+
+    /// Verify a parsed proof against a parsed verification key
+    pub fn verify(
+        &self,
+        proof: &SP1ProofWithPublicValues,
+        vk: &SP1VerifyingKey,
+    ) -> VerificationOutcome {
+        let vkey_hash = vk.bytes32();
+        let program_version = self
+            .allowed_vkeys
+            .as_ref()
+            .and_then(|allowed| allowed.lookup(&vkey_hash))
+            .map(|vkey| vkey.label.clone());
+
+        let mut checks = Checks::default();
+        match self.proof_kind {
+            Some(expected) => checks.run("proof_kind", || {
+                ProofKind::check(Some(expected), ProofKind::of(&proof.proof))
+            }),
+            None => checks.skip("proof_kind"),
+        }
+        checks.run("sp1_version", || check_sp1_version(&proof.sp1_version));
+        match &self.allowed_vkeys {
+            Some(allowed) => checks.run("vkey_allowed", || allowed.check(&vkey_hash).map(|_| ())),
+            None => checks.skip("vkey_allowed"),
+        }
+        checks.run("public_values_digest", || check_public_values(proof));
+        checks.run("curve_points", || check_groth16_points(proof));
+        checks.run("proof", || self.verify_with_client(proof, vk));
+        checks.run_consensus(
+            proof.public_values.as_slice(),
+            self.bank_hash_extras.as_ref(),
+            &self.expectations,
+        );
+        let (status, checks) = checks.finish();
+
+        VerificationOutcome {
+            status,
+            vkey_hash,
+            program_version,
+            sp1_version: proof.sp1_version.clone(),
+            public_values: proof.public_values.to_vec(),
+            checks,
+        }
+    }
+
+    fn verify_with_client(
+        &self,
+        proof: &SP1ProofWithPublicValues,
+        vk: &SP1VerifyingKey,
+    ) -> Result<(), VerifyError> {
+        // Use panic::catch_unwind to catch any panics during verification
+        let verification_result =
+            panic::catch_unwind(AssertUnwindSafe(|| self.client.verify(proof, vk)));
+
+        match verification_result {
+            Ok(result) => result.map_err(|e| from_sdk_error(e, proof)),
+            Err(panic_payload) => {
+                let panic_message = match panic_payload.downcast_ref::<String>() {
+                    Some(s) => s.to_string(),
+                    None => match panic_payload.downcast_ref::<&str>() {
+                        Some(s) => s.to_string(),
+                        None => "Unknown panic occurred during verification".to_string(),
+                    },
+                };
+
+                Err(from_panic_message(panic_message))
+            }
+        }
+    }
+
+    /// Verify a proof and verification key given as JSON bytes
+    pub fn verify_json(
+        &self,
+        proof_json: &[u8],
+        vkey_json: &[u8],
+    ) -> Result<VerificationOutcome, VerifyError> {
+        let proof = parse_proof_json(proof_json)?;
+        let vk = parse_vkey_json(vkey_json)?;
+        Ok(self.verify(&proof, &vk))
+    }
+}
+
+/// Check the public values against the digest committed by Groth16 and Plonk proofs
+fn check_public_values(proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+    let public_inputs = match &proof.proof {
+        SP1Proof::Groth16(p) => &p.public_inputs,
+        SP1Proof::Plonk(p) => &p.public_inputs,
+        SP1Proof::Compressed(_) | SP1Proof::Core(_) => return Ok(()),
+    };
+
+    check_committed_values_digest(&public_inputs[1], proof.public_values.as_slice())
+}
+
+/// Check the curve points of a Groth16 proof before handing it to the gnark verifier
+fn check_groth16_points(proof: &SP1ProofWithPublicValues) -> Result<(), VerifyError> {
+    let SP1Proof::Groth16(groth16) = &proof.proof else {
+        return Ok(());
+    };
+
+    let encoded_proof = hex::decode(&groth16.encoded_proof).map_err(|e| {
+        VerifyError::MalformedProofJson(format!("invalid encoded_proof hex: {}", e))
+    })?;
+    check_proof_points(&encoded_proof)
+}
+
+/// Classify an error returned by the SP1 prover client
+fn from_sdk_error(error: SP1VerificationError, proof: &SP1ProofWithPublicValues) -> VerifyError {
+    match error {
+        SP1VerificationError::InvalidPublicValues => VerifyError::MalformedPublicValues(
+            "the public values do not match the proof".to_string(),
+        ),
+        SP1VerificationError::VersionMismatch(_) => VerifyError::VersionMismatch {
+            proof: proof.sp1_version.clone(),
+            expected: expected_circuit_version().to_string(),
+        },
+        SP1VerificationError::Groth16(e) | SP1VerificationError::Plonk(e) => {
+            VerifyError::PairingCheckFailed(format!("{:?}", e))
+        }
+        SP1VerificationError::Core(e) => VerifyError::InvalidProof(format!("{:?}", e)),
+        SP1VerificationError::Recursion(e) => VerifyError::InvalidProof(format!("{:?}", e)),
+        other => VerifyError::Internal(other.to_string()),
+    }
+}
+
+/// Classify a panic raised while verifying.
+///
+/// The gnark verifiers report failures by panicking, so the message is the
+/// only thing to go on here.
+fn from_panic_message(panic_message: String) -> VerifyError {
+    if panic_message.contains("invalid point: subgroup check failed") {
+        VerifyError::InvalidCurvePoint(panic_message)
+    } else if panic_message.contains("failed to verify proof") {
+        VerifyError::PairingCheckFailed(panic_message)
+    } else {
+        VerifyError::Internal(panic_message)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::input::parse_vkey_json;
+use crate::native::bytes32_to_decimal;
+use serde::Deserialize;
+use sp1_sdk::HashableKey;
+use std::error::Error;
+
+/// A chip of the program's STARK verifying key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipInfo {
+    /// Chip name, such as `Program` or `Byte`
+    pub name: String,
+    /// Log2 of the trace domain size
+    pub log_n: usize,
+    /// Number of preprocessed columns
+    pub width: usize,
+    /// Number of preprocessed rows
+    pub height: usize,
+}
+
+/// Hashes and layout of a program verification key
+#[derive(Debug, Clone)]
+pub struct VkeyInfo {
+    /// Vkey hash in `bytes32()` form, as committed in Groth16 public input 0
+    pub bytes32: String,
+    /// Vkey hash as a decimal BN254 field element
+    pub hash_bn254: String,
+    /// Program counter at which execution starts
+    pub pc_start: u64,
+    /// Preprocessed chips with their trace dimensions
+    pub chips: Vec<ChipInfo>,
+}
+
+#[derive(Deserialize)]
+struct DomainJson {
+    log_n: usize,
+}
+
+#[derive(Deserialize)]
+struct DimensionsJson {
+    width: usize,
+    height: usize,
+}
+
+#[derive(Deserialize)]
+struct StarkVkeyJson {
+    pc_start: u64,
+    chip_information: Vec<(String, DomainJson, DimensionsJson)>,
+}
+
+#[derive(Deserialize)]
+struct VkeyJson {
+    vk: StarkVkeyJson,
+}
+
+/// Describe a verification key given as JSON bytes
+pub fn describe_vkey(vkey_json: &[u8]) -> Result<VkeyInfo, Box<dyn Error>> {
+    let vk = parse_vkey_json(vkey_json)?;
+    let layout: VkeyJson = serde_json::from_slice(vkey_json)?;
+
+    let bytes32 = vk.bytes32();
+    let hash_bytes: [u8; 32] = hex::decode(bytes32.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| "vkey hash is not 32 bytes")?;
+
+    let chips = layout
+        .vk
+        .chip_information
+        .into_iter()
+        .map(|(name, domain, dimensions)| ChipInfo {
+            name,
+            log_n: domain.log_n,
+            width: dimensions.width,
+            height: dimensions.height,
+        })
+        .collect();
+
+    Ok(VkeyInfo {
+        bytes32,
+        hash_bn254: bytes32_to_decimal(&hash_bytes),
+        pc_start: layout.vk.pc_start,
+        chips,
+    })
+}
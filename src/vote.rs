@@ -0,0 +1,471 @@
+//! Validator vote transactions.
+//!
+//! A vote record carries a legacy transaction message and the signature of
+//! the validator's vote authority over its wire bytes. The message holds one
+//! vote program instruction; for the compact vote state updates and tower
+//! syncs that validators send, its data is a `u32` LE instruction index
+//! followed by:
+//!
+//! - root slot as `u64` LE, `u64::MAX` for none
+//! - lockouts as a compact-u16 count of (varint slot offset, `u8` confirmation count)
+//! - the bank hash of the last voted slot
+//! - an optional `i64` LE timestamp
+//! - for tower syncs, the block id of the last voted slot
+//! - for switch variants, the switch proof hash
+//!
+//! Lockout slots are offsets from the previous lockout, starting at the root.
+
+use crate::message::Message;
+use crate::public_values::{ConsensusPackage, Hash, Pubkey, Vote, VoteTransaction};
+use crate::short_vec::ShortU16;
+use ed25519_dalek::{Signature, VerifyingKey};
+use std::error::Error;
+use std::fmt;
+
+/// Address of the vote program, `Vote111111111111111111111111111111111111111`
+pub const VOTE_PROGRAM_ID: Pubkey = [
+    7, 97, 72, 29, 53, 116, 116, 187, 124, 77, 118, 36, 235, 211, 189, 179, 216, 53, 94, 115, 209,
+    16, 67, 252, 13, 163, 83, 128, 0, 0, 0, 0,
+];
+
+const COMPACT_UPDATE_VOTE_STATE: u32 = 12;
+const COMPACT_UPDATE_VOTE_STATE_SWITCH: u32 = 13;
+const TOWER_SYNC: u32 = 14;
+const TOWER_SYNC_SWITCH: u32 = 15;
+
+/// A voted slot and the number of votes stacked on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lockout {
+    pub slot: u64,
+    pub confirmation_count: u8,
+}
+
+/// A decoded compact vote state update or tower sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TowerSync {
+    /// Whether this is a tower sync rather than a vote state update
+    pub is_tower_sync: bool,
+    /// Root slot of the validator's tower
+    pub root: Option<u64>,
+    /// Voted slots above the root, oldest first
+    pub lockouts: Vec<Lockout>,
+    /// Bank hash of the last voted slot
+    pub hash: Hash,
+    /// Unix time at which the vote was made
+    pub timestamp: Option<i64>,
+    /// Block id of the last voted slot, for tower syncs
+    pub block_id: Option<Hash>,
+    /// Hash of the switch proof, for switch variants
+    pub switch_proof_hash: Option<Hash>,
+}
+
+impl TowerSync {
+    /// The slot whose bank hash the vote carries
+    pub fn last_voted_slot(&self) -> Option<u64> {
+        self.lockouts.last().map(|lockout| lockout.slot)
+    }
+
+    /// Whether the tower covers a slot, as its root or one of its lockouts
+    pub fn covers(&self, slot: u64) -> bool {
+        self.root == Some(slot) || self.lockouts.iter().any(|lockout| lockout.slot == slot)
+    }
+}
+
+/// Why a vote record is invalid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoteError {
+    /// The message has no vote program instruction
+    NoVoteInstruction,
+    /// The vote instruction is not a compact vote state update or tower sync
+    UnsupportedInstruction(u32),
+    /// The vote instruction data could not be decoded
+    MalformedInstruction(String),
+    /// The record is tagged with a different instruction than its message carries
+    InstructionKind { tower_sync: bool },
+    /// The voter is not one of the message signers
+    NotSigner,
+    /// The voter public key is not a valid ed25519 point
+    InvalidPublicKey,
+    /// The signature does not verify against the voter and message
+    InvalidSignature,
+    /// The record's hash is not the one in the vote instruction
+    HashMismatch { record: Hash, instruction: Hash },
+    /// The record's slot is neither the root nor a lockout of the tower
+    SlotNotInTower { slot: u64 },
+    /// The record's lockout count is not the number of lockouts in the tower
+    LockoutCount { record: u64, instruction: usize },
+}
+
+impl fmt::Display for VoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoteError::NoVoteInstruction => f.write_str("message has no vote instruction"),
+            VoteError::UnsupportedInstruction(index) => {
+                write!(f, "unsupported vote instruction {}", index)
+            }
+            VoteError::MalformedInstruction(e) => write!(f, "malformed vote instruction: {}", e),
+            VoteError::InstructionKind { tower_sync } => write!(
+                f,
+                "record is tagged {} but the message carries a {}",
+                if *tower_sync {
+                    "CompactUpdateVoteState"
+                } else {
+                    "TowerSync"
+                },
+                if *tower_sync {
+                    "tower sync"
+                } else {
+                    "vote state update"
+                }
+            ),
+            VoteError::NotSigner => f.write_str("voter did not sign the message"),
+            VoteError::InvalidPublicKey => {
+                f.write_str("voter public key is not a valid ed25519 key")
+            }
+            VoteError::InvalidSignature => f.write_str("ed25519 signature does not verify"),
+            VoteError::HashMismatch {
+                record,
+                instruction,
+            } => write!(
+                f,
+                "record votes for hash {} but the instruction carries {}",
+                bs58::encode(record).into_string(),
+                bs58::encode(instruction).into_string()
+            ),
+            VoteError::SlotNotInTower { slot } => {
+                write!(f, "slot {} is not in the voted tower", slot)
+            }
+            VoteError::LockoutCount {
+                record,
+                instruction,
+            } => write!(
+                f,
+                "record has {} lockouts but the instruction carries {}",
+                record, instruction
+            ),
+        }
+    }
+}
+
+impl Error for VoteError {}
+
+/// Reads the fields of vote instruction data
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], VoteError> {
+        if self.data.len() < len {
+            return Err(VoteError::MalformedInstruction(
+                "unexpected end of data".to_string(),
+            ));
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, VoteError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, VoteError> {
+        Ok(u32::from_le_bytes(
+            self.take(4)?.try_into().expect("4 bytes"),
+        ))
+    }
+
+    fn u64(&mut self) -> Result<u64, VoteError> {
+        Ok(u64::from_le_bytes(
+            self.take(8)?.try_into().expect("8 bytes"),
+        ))
+    }
+
+    fn hash(&mut self) -> Result<Hash, VoteError> {
+        Ok(self.take(32)?.try_into().expect("32 bytes"))
+    }
+
+    /// LEB128 encoded `u64`
+    fn varint(&mut self) -> Result<u64, VoteError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            // Only the lowest bit of the tenth byte fits in a u64
+            if shift == 63 && byte & 0x7e != 0 {
+                break;
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(VoteError::MalformedInstruction(
+            "varint overflows u64".to_string(),
+        ))
+    }
+
+    /// Solana's compact-u16 length prefix
+    fn compact_u16(&mut self) -> Result<usize, VoteError> {
+        let ShortU16(len) = bincode::deserialize_from(&mut self.data)
+            .map_err(|e| VoteError::MalformedInstruction(e.to_string()))?;
+        Ok(len.into())
+    }
+
+    fn option<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T, VoteError>,
+    ) -> Result<Option<T>, VoteError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => read(self).map(Some),
+            tag => Err(VoteError::MalformedInstruction(format!(
+                "invalid option tag {}",
+                tag
+            ))),
+        }
+    }
+}
+
+/// Decode the data of a compact vote state update or tower sync instruction
+pub fn decode_vote_instruction(data: &[u8]) -> Result<TowerSync, VoteError> {
+    let mut reader = Reader { data };
+    let index = reader.u32()?;
+    let (is_tower_sync, is_switch) = match index {
+        COMPACT_UPDATE_VOTE_STATE => (false, false),
+        COMPACT_UPDATE_VOTE_STATE_SWITCH => (false, true),
+        TOWER_SYNC => (true, false),
+        TOWER_SYNC_SWITCH => (true, true),
+        index => return Err(VoteError::UnsupportedInstruction(index)),
+    };
+
+    let root = Some(reader.u64()?).filter(|&root| root != u64::MAX);
+    let count = reader.compact_u16()?;
+    let mut slot = root.unwrap_or_default();
+    let mut lockouts = Vec::with_capacity(count);
+    for _ in 0..count {
+        let offset = reader.varint()?;
+        slot = slot
+            .checked_add(offset)
+            .ok_or_else(|| VoteError::MalformedInstruction("lockout slot overflows".to_string()))?;
+        lockouts.push(Lockout {
+            slot,
+            confirmation_count: reader.u8()?,
+        });
+    }
+    let hash = reader.hash()?;
+    let timestamp = reader.option(|reader| reader.u64().map(|t| t as i64))?;
+    let block_id = if is_tower_sync {
+        Some(reader.hash()?)
+    } else {
+        None
+    };
+    let switch_proof_hash = if is_switch {
+        Some(reader.hash()?)
+    } else {
+        None
+    };
+
+    if !reader.data.is_empty() {
+        return Err(VoteError::MalformedInstruction(format!(
+            "{} trailing bytes",
+            reader.data.len()
+        )));
+    }
+
+    Ok(TowerSync {
+        is_tower_sync,
+        root,
+        lockouts,
+        hash,
+        timestamp,
+        block_id,
+        switch_proof_hash,
+    })
+}
+
+/// Find and decode the vote program instruction of a message
+pub fn vote_instruction(message: &Message) -> Result<TowerSync, VoteError> {
+    let instruction = message
+        .instructions
+        .iter()
+        .find(|instruction| {
+            message
+                .account_keys
+                .get(instruction.program_id_index as usize)
+                == Some(&VOTE_PROGRAM_ID)
+        })
+        .ok_or(VoteError::NoVoteInstruction)?;
+    decode_vote_instruction(&instruction.data)
+}
+
+/// Verify the voter's ed25519 signature over the wire bytes of the message
+pub fn verify_vote_signature(tx: &VoteTransaction) -> Result<(), VoteError> {
+    let signers = tx
+        .message
+        .account_keys
+        .iter()
+        .take(tx.message.header.num_required_signatures as usize);
+    if !signers.into_iter().any(|key| *key == tx.voter_pubkey) {
+        return Err(VoteError::NotSigner);
+    }
+
+    let key =
+        VerifyingKey::from_bytes(&tx.voter_pubkey).map_err(|_| VoteError::InvalidPublicKey)?;
+    let signature =
+        Signature::from_slice(&tx.signature).map_err(|_| VoteError::InvalidSignature)?;
+    let message = bincode::serialize(&tx.message)
+        .map_err(|e| VoteError::MalformedInstruction(e.to_string()))?;
+    key.verify_strict(&message, &signature)
+        .map_err(|_| VoteError::InvalidSignature)
+}
+
+/// Check a vote record: its signature, and that it matches the instruction it carries
+pub fn check_vote(vote: &Vote) -> Result<TowerSync, VoteError> {
+    let tx = vote.transaction();
+    verify_vote_signature(tx)?;
+
+    let tower = vote_instruction(&tx.message)?;
+    let tagged_tower_sync = matches!(vote, Vote::TowerSync(_));
+    if tower.is_tower_sync != tagged_tower_sync {
+        return Err(VoteError::InstructionKind {
+            tower_sync: tower.is_tower_sync,
+        });
+    }
+    if tx.vote_for_hash != tower.hash {
+        return Err(VoteError::HashMismatch {
+            record: tx.vote_for_hash,
+            instruction: tower.hash,
+        });
+    }
+    if !tower.covers(tx.vote_for_slot) {
+        return Err(VoteError::SlotNotInTower {
+            slot: tx.vote_for_slot,
+        });
+    }
+    if tx.lockouts != tower.lockouts.len() as u64 {
+        return Err(VoteError::LockoutCount {
+            record: tx.lockouts,
+            instruction: tower.lockouts.len(),
+        });
+    }
+    Ok(tower)
+}
+
+/// Check every vote of a package, returning the voter of each invalid vote
+pub fn check_votes(package: &ConsensusPackage) -> Vec<(Pubkey, VoteError)> {
+    package
+        .votes
+        .iter()
+        .filter_map(|vote| {
+            check_vote(vote)
+                .err()
+                .map(|error| (vote.transaction().voter_pubkey, error))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::load_package;
+    use std::path::Path;
+
+    fn sample_votes() -> Vec<Vote> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/package_solana.json");
+        load_package(Path::new(path)).unwrap().votes
+    }
+
+    fn transaction_mut(vote: &mut Vote) -> &mut VoteTransaction {
+        match vote {
+            Vote::CompactUpdateVoteState(tx) | Vote::TowerSync(tx) => tx,
+        }
+    }
+
+    #[test]
+    fn checks_every_sample_vote() {
+        let votes = sample_votes();
+        assert_eq!(votes.len(), 81);
+        for vote in &votes {
+            check_vote(vote).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_modified_signature() {
+        let mut vote = sample_votes().remove(0);
+        transaction_mut(&mut vote).signature[0] ^= 0x01;
+
+        assert_eq!(check_vote(&vote).unwrap_err(), VoteError::InvalidSignature);
+    }
+
+    #[test]
+    fn rejects_truncated_instruction() {
+        let vote = &sample_votes()[0];
+        let data = &vote_instruction_data(&vote.transaction().message);
+        decode_vote_instruction(data).unwrap();
+
+        assert!(matches!(
+            decode_vote_instruction(&data[..data.len() - 1]),
+            Err(VoteError::MalformedInstruction(_))
+        ));
+    }
+
+    /// Compact vote state update data with the given lockout count and offsets
+    fn instruction_data(count: &[u8], offsets: &[&[u8]]) -> Vec<u8> {
+        let mut data = COMPACT_UPDATE_VOTE_STATE.to_le_bytes().to_vec();
+        data.extend(100u64.to_le_bytes());
+        data.extend(count);
+        for offset in offsets {
+            data.extend(*offset);
+            data.push(1);
+        }
+        data.extend([7; 32]);
+        data.push(0);
+        data
+    }
+
+    #[test]
+    fn decodes_lockout_offsets() {
+        let data = instruction_data(&[2], &[&[1], &[0x80, 0x01]]);
+        let tower = decode_vote_instruction(&data).unwrap();
+
+        let slots: Vec<_> = tower.lockouts.iter().map(|lockout| lockout.slot).collect();
+        assert_eq!(slots, vec![101, 229]);
+    }
+
+    #[test]
+    fn rejects_non_minimal_lockout_count() {
+        let data = instruction_data(&[0x81, 0x00], &[&[1]]);
+        assert!(matches!(
+            decode_vote_instruction(&data),
+            Err(VoteError::MalformedInstruction(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_overflowing_varint() {
+        let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        let mut reader = Reader { data: &max };
+        assert_eq!(reader.varint(), Ok(u64::MAX));
+
+        let overflow = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        let mut reader = Reader { data: &overflow };
+        assert!(matches!(
+            reader.varint(),
+            Err(VoteError::MalformedInstruction(_))
+        ));
+    }
+
+    fn vote_instruction_data(message: &Message) -> Vec<u8> {
+        message
+            .instructions
+            .iter()
+            .find(|instruction| {
+                message.account_keys[instruction.program_id_index as usize] == VOTE_PROGRAM_ID
+            })
+            .unwrap()
+            .data
+            .clone()
+    }
+}